@@ -2,10 +2,11 @@
 const cluster = require('cluster');
 const http = require('http');
 const fs = require('fs');
-const numCPUs = require('os').cpus().length;
+let numCPUs = require('os').cpus().length;
 
 var greetingRe = new RegExp("^\/greeting\/([a-z]+)$", "i");
 var portRe = new RegExp("^--port=(\\d+)$", "i");
+var workersRe = new RegExp("^--workers=(\\d+)$", "i");
 
 var port = 3000;
 process.argv.forEach((val, index) => {
@@ -13,6 +14,10 @@ process.argv.forEach((val, index) => {
     if (match) {
         port = parseInt(match[1]);
     }
+    match = workersRe.exec(val);
+    if (match) {
+        numCPUs = parseInt(match[1]);
+    }
 });
 
 if (cluster.isMaster) {
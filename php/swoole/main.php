@@ -3,10 +3,13 @@ use OpenSwoole\Http\Server;
 use OpenSwoole\Http\Request;
 use OpenSwoole\Http\Response;
 
-$options = getopt("", ["port::"]);
+$options = getopt("", ["port::", "workers::"]);
 $port = array_key_exists("port", $options) ? intval($options["port"]) : 3000;
 
 $http = new OpenSwoole\HTTP\Server('127.0.0.1', $port);
+if (array_key_exists("workers", $options)) {
+    $http->set(['worker_num' => intval($options["workers"])]);
+}
 $pattern = '/\/greeting\/([a-z]+)/';
 
 $http->on('request', function (Request $request, Response $response) use ($pattern) {
@@ -38,6 +38,10 @@ versions() {
     cargo run --manifest-path suite/Cargo.toml --bin versions
 }
 
+update_versions() {
+    cargo run --manifest-path suite/Cargo.toml --bin versions -- --update-readme
+}
+
 lint() {
     docker run --rm -i hadolint/hadolint < suite/Dockerfile
 }
@@ -0,0 +1,183 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+
+/// The size of the kernel `cpu_set_t` mask.
+const MAX_CPUS: usize = 1024;
+
+/// A set of CPUs in the Linux cpu-list format, e.g. `0-3,6`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuSet(BTreeSet<usize>);
+
+impl CpuSet {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the subset with the first `count` CPUs.
+    pub fn first(&self, count: usize) -> CpuSet {
+        CpuSet(self.0.iter().take(count).copied().collect())
+    }
+
+    pub fn overlaps(&self, other: &CpuSet) -> bool {
+        !self.0.is_disjoint(&other.0)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn mask(&self) -> libc::cpu_set_t {
+        let mut set = unsafe { std::mem::zeroed::<libc::cpu_set_t>() };
+        for cpu in &self.0 {
+            unsafe { libc::CPU_SET(*cpu, &mut set) };
+        }
+        set
+    }
+
+    /// Pins the process (and all its children) to the CPU set before it is executed.
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self, cmd: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        // The set is prepared in advance as only async-signal-safe calls are
+        // allowed in the forked child.
+        let set = self.mask();
+        unsafe {
+            cmd.pre_exec(move || {
+                if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply(&self, _cmd: &mut Command) {
+        eprintln!("Warning: CPU affinity is supported on Linux only, ignoring {self}");
+    }
+
+    /// Pins the calling thread to the CPU set (the failure is ignored, as the thread could still
+    /// do its job anywhere).
+    #[cfg(target_os = "linux")]
+    pub fn pin_current_thread(&self) {
+        let set = self.mask();
+        unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn pin_current_thread(&self) {}
+}
+
+impl FromStr for CpuSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = BTreeSet::new();
+        for part in s.split(',').map(str::trim) {
+            let parse = |x: &str| {
+                x.parse::<usize>()
+                    .map_err(|_| format!("invalid CPU number '{x}'"))
+            };
+            let (first, last) = match part.split_once('-') {
+                Some((first, last)) => (parse(first)?, parse(last)?),
+                None => (parse(part)?, parse(part)?),
+            };
+            if first > last {
+                return Err(format!("invalid CPU range '{part}'"));
+            }
+            if last >= MAX_CPUS {
+                return Err(format!("CPU {last} is out of range"));
+            }
+            result.extend(first..=last);
+        }
+        Ok(CpuSet(result))
+    }
+}
+
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for &cpu in &self.0 {
+            match ranges.last_mut() {
+                Some(last) if last.1 + 1 == cpu => last.1 = cpu,
+                _ => ranges.push((cpu, cpu)),
+            }
+        }
+        let parts: Vec<String> = ranges
+            .iter()
+            .map(|(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{first}-{last}")
+                }
+            })
+            .collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// Returns the CPUs the suite itself is allowed to run on.
+pub fn current() -> Option<CpuSet> {
+    allowed_cpus(std::process::id())?.parse().ok()
+}
+
+/// Returns the CPUs the process is allowed to run on (as reported by the kernel).
+pub fn allowed_cpus(pid: u32) -> Option<String> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+        .map(|x| x.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpus(s: &str) -> CpuSet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_lists_and_ranges() {
+        assert_eq!(cpus("0-3,6"), CpuSet(BTreeSet::from([0, 1, 2, 3, 6])));
+        assert_eq!(cpus("5"), CpuSet(BTreeSet::from([5])));
+        assert_eq!(cpus("2-2"), CpuSet(BTreeSet::from([2])));
+        assert_eq!(cpus(" 1 , 0-1 "), CpuSet(BTreeSet::from([0, 1])));
+        assert_eq!(cpus("1023").len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        for input in ["", "a", "1,", "-1", "1-", "1-2-3", "3-1", "1024", "0-1024"] {
+            assert!(input.parse::<CpuSet>().is_err(), "{input} is accepted");
+        }
+        assert_eq!(
+            "3-1".parse::<CpuSet>(),
+            Err(String::from("invalid CPU range '3-1'"))
+        );
+        assert_eq!(
+            "1024".parse::<CpuSet>(),
+            Err(String::from("CPU 1024 is out of range"))
+        );
+    }
+
+    #[test]
+    fn displays_compact_ranges() {
+        assert_eq!(cpus("0,1,2,3,6").to_string(), "0-3,6");
+        assert_eq!(cpus("7,5").to_string(), "5,7");
+        assert_eq!(cpus("4-5,6-8").to_string(), "4-8");
+        for input in ["0", "0-3,6", "1,3,5-7"] {
+            assert_eq!(cpus(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn first_and_overlaps() {
+        assert_eq!(cpus("2-5,8").first(2), cpus("2-3"));
+        assert_eq!(cpus("2-3").first(4), cpus("2-3"));
+        assert!(cpus("0-3").overlaps(&cpus("3-4")));
+        assert!(!cpus("0-3").overlaps(&cpus("4-7")));
+    }
+}
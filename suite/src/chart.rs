@@ -0,0 +1,881 @@
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use itertools::Itertools;
+use plotters::data::fitting_range;
+use plotters::element::DashedPathElement;
+use plotters::prelude::*;
+use std::error::Error;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::host::HostInfo;
+use crate::resources::ServerUsage;
+use crate::stats::{self, Sample};
+
+/// The measured samples of a language in a scenario.
+#[derive(Clone, Copy)]
+pub struct Series<'a> {
+    pub title: &'a str,
+    pub scenario: &'a str,
+    /// The position of the scenario in the registry, which picks the color.
+    pub scenario_idx: usize,
+    pub samples: &'a [Sample],
+    /// The resources used by the server, if sampled.
+    pub usage: Option<ServerUsage>,
+}
+
+/// The language failed to be measured, shown as the annotated gap.
+pub struct Gap {
+    pub title: String,
+    pub note: String,
+}
+
+/// The percentile the histograms, the CDF curves and the violins are cut off at, so the few
+/// slowest requests don't squash the shape of the distributions.
+const TAIL_PERCENTILE: f64 = 99.9;
+const HISTOGRAM_BINS: usize = 60;
+/// The number of the points the violin outline is evaluated at.
+const DENSITY_POINTS: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChartType {
+    Boxplot,
+    Violin,
+    Histogram,
+    Cdf,
+}
+
+impl ValueEnum for ChartType {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            ChartType::Boxplot,
+            ChartType::Violin,
+            ChartType::Histogram,
+            ChartType::Cdf,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(match self {
+            ChartType::Boxplot => "boxplot",
+            ChartType::Violin => "violin",
+            ChartType::Histogram => "histogram",
+            ChartType::Cdf => "cdf",
+        }))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| format!("expected the size as WIDTHxHEIGHT, got '{s}'"))?;
+        let parse = |x: &str| match x.parse::<u32>() {
+            Ok(value) if value >= 100 => Ok(value),
+            _ => Err(format!("invalid dimension '{x}' (at least 100 pixels)")),
+        };
+        Ok(Size {
+            width: parse(width)?,
+            height: parse(height)?,
+        })
+    }
+}
+
+impl Size {
+    pub fn pixels(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// The order of the languages (the best ones are at the top).
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Median,
+    P99,
+    Name,
+    Rps,
+}
+
+impl ValueEnum for SortKey {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[SortKey::Median, SortKey::P99, SortKey::Name, SortKey::Rps]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(match self {
+            SortKey::Median => "median",
+            SortKey::P99 => "p99",
+            SortKey::Name => "name",
+            SortKey::Rps => "rps",
+        }))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl ValueEnum for Theme {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Theme::Light, Theme::Dark]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }))
+    }
+}
+
+impl Theme {
+    pub fn background(&self) -> RGBColor {
+        match self {
+            Theme::Light => WHITE,
+            Theme::Dark => RGBColor(32, 33, 36),
+        }
+    }
+
+    pub fn foreground(&self) -> RGBColor {
+        match self {
+            Theme::Light => BLACK,
+            Theme::Dark => RGBColor(220, 220, 220),
+        }
+    }
+
+    pub fn grid(&self) -> RGBAColor {
+        self.foreground().mix(0.15)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Legend {
+    UpperLeft,
+    UpperRight,
+    LowerLeft,
+    LowerRight,
+    None,
+}
+
+impl ValueEnum for Legend {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Legend::UpperLeft,
+            Legend::UpperRight,
+            Legend::LowerLeft,
+            Legend::LowerRight,
+            Legend::None,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(match self {
+            Legend::UpperLeft => "upper-left",
+            Legend::UpperRight => "upper-right",
+            Legend::LowerLeft => "lower-left",
+            Legend::LowerRight => "lower-right",
+            Legend::None => "none",
+        }))
+    }
+}
+
+impl Legend {
+    fn position(&self) -> Option<SeriesLabelPosition> {
+        match self {
+            Legend::UpperLeft => Some(SeriesLabelPosition::UpperLeft),
+            Legend::UpperRight => Some(SeriesLabelPosition::UpperRight),
+            Legend::LowerLeft => Some(SeriesLabelPosition::LowerLeft),
+            Legend::LowerRight => Some(SeriesLabelPosition::LowerRight),
+            Legend::None => None,
+        }
+    }
+}
+
+/// The look of the main chart.
+#[derive(Clone)]
+pub struct ChartOptions {
+    pub kind: ChartType,
+    pub size: Size,
+    /// The logarithmic response time axis (for the results differing by the orders of magnitude).
+    pub log_scale: bool,
+    pub sort: SortKey,
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub theme: Theme,
+    pub legend: Legend,
+}
+
+impl Default for ChartOptions {
+    fn default() -> ChartOptions {
+        ChartOptions {
+            kind: ChartType::Boxplot,
+            size: Size {
+                width: 480,
+                height: 640,
+            },
+            log_scale: false,
+            sort: SortKey::Median,
+            title: None,
+            subtitle: None,
+            theme: Theme::Light,
+            legend: Legend::UpperRight,
+        }
+    }
+}
+
+/// The color of the scenario, which doesn't depend on the other measured scenarios.
+pub fn scenario_color(idx: usize) -> RGBColor {
+    let (r, g, b) = Palette99::pick(idx).rgb();
+    RGBColor(r, g, b)
+}
+
+/// Returns the languages ordered from the bottom of the chart to the top: the worst first,
+/// so the best ones are at the top (or alphabetically from the top for the name).
+pub fn sorted_titles(series: &[Series], sort: SortKey) -> Vec<String> {
+    // The languages are ordered by the first scenario
+    let first: Vec<&Series> = series.iter().unique_by(|x| x.title).collect();
+    let key = |x: &Series| {
+        let latencies = stats::sorted(stats::latencies(x.samples).into_iter());
+        match sort {
+            SortKey::Median => stats::percentile(&latencies, 50.0),
+            SortKey::P99 => stats::percentile(&latencies, 99.0),
+            // The lower throughput is worse
+            SortKey::Rps => -stats::rps(x.samples),
+            SortKey::Name => 0.0,
+        }
+    };
+    let mut keyed: Vec<(f64, &str)> = first.iter().map(|x| (key(x), x.title)).collect();
+    if sort == SortKey::Name {
+        keyed.sort_by(|a, b| b.1.cmp(a.1));
+    } else {
+        keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+    }
+    keyed
+        .into_iter()
+        .map(|(_, title)| title.to_string())
+        .collect()
+}
+
+/// Formats the axis value in milliseconds with two significant digits (for the log scale).
+fn format_ms(value: f64) -> String {
+    if value >= 10.0 {
+        format!("{value:.0}")
+    } else if value >= 1.0 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+/// Converts the backend coordinates (e.g. of the chart values) to the ones within the area.
+fn relative<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    (x, y): (i32, i32),
+) -> (i32, i32) {
+    let (base_x, base_y) = area.get_base_pixel();
+    (x - base_x, y - base_y)
+}
+
+/// Fills the background, draws the title and the subtitle (if any) and the host footer, and
+/// returns the area for the chart.
+pub fn frame<DB: DrawingBackend>(
+    backend: DB,
+    host: &HostInfo,
+    opts: &ChartOptions,
+) -> Result<DrawingArea<DB, plotters::coord::Shift>, Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let root = backend.into_drawing_area();
+    root.fill(&opts.theme.background())?;
+    let mut root = host.footer(&root, &opts.theme.foreground())?;
+    if let Some(title) = &opts.title {
+        root = root.titled(
+            title,
+            ("sans-serif", 20)
+                .into_font()
+                .color(&opts.theme.foreground()),
+        )?;
+    }
+    if let Some(subtitle) = &opts.subtitle {
+        root = root.titled(
+            subtitle,
+            ("sans-serif", 13)
+                .into_font()
+                .color(&opts.theme.foreground().mix(0.7)),
+        )?;
+    }
+    Ok(root)
+}
+
+/// Converts the response time to the axis value (the log scale is drawn as the linear axis of
+/// the logarithms).
+fn scale(value: f64, log_scale: bool) -> f64 {
+    if log_scale {
+        value.max(f64::MIN_POSITIVE).log10()
+    } else {
+        value
+    }
+}
+
+/// Returns the response time axis covering the values (already scaled).
+fn x_range(values: Range<f32>, log_scale: bool) -> Range<f32> {
+    if log_scale {
+        values.start.floor()..values.end.ceil().max(values.start.floor() + 1.0)
+    } else {
+        0.0..values.end + 1.0
+    }
+}
+
+fn x_desc(opts: &ChartOptions) -> &'static str {
+    if opts.log_scale {
+        "Response, ms (log scale)"
+    } else {
+        "Response, ms"
+    }
+}
+
+fn x_label(value: f32, opts: &ChartOptions) -> String {
+    if opts.log_scale {
+        format_ms(10f64.powf(value as f64))
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Returns the response time of the tail percentile of every series (already scaled).
+fn tail_limit(series: &[Series], log_scale: bool) -> f32 {
+    series
+        .iter()
+        .map(|x| {
+            let latencies = stats::sorted(stats::latencies(x.samples).into_iter());
+            scale(stats::percentile(&latencies, TAIL_PERCENTILE), log_scale) as f32
+        })
+        .fold(f32::MIN, f32::max)
+}
+
+/// Returns the range of the violin of the sorted values, cutting off the thin tails at both ends.
+fn violin_bounds(sorted: &[f64]) -> [f64; 2] {
+    [
+        stats::percentile(sorted, 100.0 - TAIL_PERCENTILE),
+        stats::percentile(sorted, TAIL_PERCENTILE),
+    ]
+}
+
+/// Estimates the density of the sorted values within the range with the gaussian kernel (applied
+/// to the fine histogram, so the large samples are cheap), normalized to the maximum of 1.
+fn density(sorted: &[f64], range: Range<f64>) -> Vec<(f64, f64)> {
+    let width = (range.end - range.start) / (DENSITY_POINTS - 1) as f64;
+    let mut counts = vec![0.0; DENSITY_POINTS];
+    for value in sorted
+        .iter()
+        .filter(|x| (range.start..=range.end).contains(x))
+    {
+        counts[((value - range.start) / width).round() as usize] += 1.0;
+    }
+    // Silverman's rule of thumb for the bandwidth, in bins
+    let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+    let deviation =
+        (sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / sorted.len() as f64).sqrt();
+    let iqr = stats::percentile(sorted, 75.0) - stats::percentile(sorted, 25.0);
+    let spread = if iqr > 0.0 {
+        deviation.min(iqr / 1.34)
+    } else {
+        deviation
+    };
+    let bandwidth =
+        (0.9 * spread * (sorted.len() as f64).powf(-0.2) / width.max(f64::MIN_POSITIVE)).max(1.0);
+    let smoothed: Vec<f64> = (0..DENSITY_POINTS)
+        .map(|i| {
+            counts
+                .iter()
+                .enumerate()
+                .map(|(j, count)| {
+                    count * (-0.5 * ((i as f64 - j as f64) / bandwidth).powi(2)).exp()
+                })
+                .sum()
+        })
+        .collect();
+    let max = smoothed.iter().copied().fold(f64::MIN_POSITIVE, f64::max);
+    smoothed
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| (range.start + i as f64 * width, x / max))
+        .collect()
+}
+
+/// Draws the chart of the response time of every language and scenario.
+pub fn draw<DB: DrawingBackend>(
+    series: &[Series],
+    gaps: &[Gap],
+    host: &HostInfo,
+    opts: &ChartOptions,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    // The results saved without any samples have nothing to draw
+    let series: Vec<Series> = series
+        .iter()
+        .filter(|x| !x.samples.is_empty())
+        .copied()
+        .collect();
+    match opts.kind {
+        ChartType::Boxplot | ChartType::Violin => rows(&series, gaps, host, opts, backend),
+        ChartType::Histogram | ChartType::Cdf => overlay(&series, gaps, host, opts, backend),
+    }
+}
+
+/// Draws the horizontal boxplots or violins, a row per language with the scenarios side by side.
+fn rows<DB: DrawingBackend>(
+    series: &[Series],
+    gaps: &[Gap],
+    host: &HostInfo,
+    opts: &ChartOptions,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    // The failed languages are shown as the annotated gaps at the bottom
+    let lang_list: Vec<String> = gaps
+        .iter()
+        .map(|x| x.title.clone())
+        .chain(sorted_titles(series, opts.sort))
+        .collect();
+
+    let scaled: Vec<(&Series, Vec<f64>)> = series
+        .iter()
+        .map(|x| {
+            let values = stats::sorted(
+                stats::latencies(x.samples)
+                    .into_iter()
+                    .map(|value| scale(value, opts.log_scale)),
+            );
+            (x, values)
+        })
+        .collect();
+    let quartiles: Vec<(&Series, Quartiles)> = scaled
+        .iter()
+        .map(|(x, values)| (*x, Quartiles::new(values)))
+        .collect();
+    let values_range = if opts.kind == ChartType::Violin {
+        let values: Vec<f32> = scaled
+            .iter()
+            .flat_map(|(_, values)| violin_bounds(values))
+            .map(|x| x as f32)
+            .collect();
+        fitting_range(values.iter())
+    } else {
+        let values: Vec<f32> = quartiles
+            .iter()
+            .flat_map(|x| x.1.values().to_vec())
+            .collect();
+        fitting_range(values.iter())
+    };
+    let x_range = x_range(values_range, opts.log_scale);
+
+    let foreground = opts.theme.foreground();
+    let root = frame(backend, host, opts)?;
+    let mut chart = ChartBuilder::on(&root)
+        .x_label_area_size(40)
+        .y_label_area_size(100)
+        .build_cartesian_2d(x_range.clone(), lang_list[..].into_segmented())?;
+
+    chart
+        .configure_mesh()
+        .x_desc(x_desc(opts))
+        .y_desc("Language")
+        .y_labels(lang_list.len())
+        .x_label_formatter(&|x| x_label(*x, opts))
+        .axis_style(foreground)
+        .label_style(("sans-serif", 12).into_font().color(&foreground))
+        .axis_desc_style(("sans-serif", 14).into_font().color(&foreground))
+        .bold_line_style(opts.theme.grid())
+        .light_line_style(opts.theme.background())
+        .draw()?;
+
+    let scenarios: Vec<(&str, usize)> = series
+        .iter()
+        .map(|x| (x.scenario, x.scenario_idx))
+        .unique()
+        .sorted_by_key(|x| x.1)
+        .collect();
+    // Without the measured languages only the gaps are drawn
+    let count = scenarios.len().max(1) as i32;
+    let row_height = chart.plotting_area().dim_in_pixel().1 as i32 / lang_list.len().max(1) as i32;
+    for (position, (scenario, idx)) in scenarios.into_iter().enumerate() {
+        let style = scenario_color(idx);
+        // The shapes of the scenarios are stacked around the center of the language row
+        let offset = (position as i32 * 2 - (count - 1)) * 10;
+        let rows = quartiles
+            .iter()
+            .zip(&scaled)
+            .filter(|x| x.0 .0.scenario == scenario)
+            .filter_map(|(x, (_, values))| {
+                let title = lang_list.iter().find(|title| *title == x.0.title)?;
+                Some((title, &x.1, values))
+            });
+        if opts.kind == ChartType::Violin {
+            let half_width = (row_height * 2 / 5 / count).max(4) as f64;
+            let offset = offset * half_width as i32 / 10;
+            for (title, quartiles, values) in rows {
+                let [min, max] = violin_bounds(values);
+                let outline = density(values, min..max);
+                let point = |x: f64, density: f64| {
+                    let (x, y) = relative(
+                        &root,
+                        chart.backend_coord(&(x as f32, SegmentValue::CenterOf(title))),
+                    );
+                    (x, y + offset - (density * half_width) as i32)
+                };
+                let upper = outline.iter().map(|(x, density)| point(*x, *density));
+                let lower = outline.iter().rev().map(|(x, density)| point(*x, -density));
+                let polygon: Vec<(i32, i32)> = upper.chain(lower).collect();
+                root.draw(&Polygon::new(polygon.clone(), style.mix(0.3).filled()))?;
+                root.draw(&PathElement::new(polygon, style))?;
+                let median = point(quartiles.median(), 0.0);
+                root.draw(&PathElement::new(
+                    [
+                        (median.0, median.1 - half_width as i32),
+                        (median.0, median.1 + half_width as i32),
+                    ],
+                    style.stroke_width(2),
+                ))?;
+            }
+            // Only for the legend
+            chart.draw_series(std::iter::empty::<Circle<(f32, SegmentValue<&String>), i32>>())?
+        } else {
+            chart.draw_series(rows.map(|(title, quartiles, _)| {
+                Boxplot::new_horizontal(SegmentValue::CenterOf(title), quartiles)
+                    .width(10)
+                    .whisker_width(0.5)
+                    .style(style)
+                    .offset(offset)
+            }))?
+        }
+        .label(scenario)
+        .legend(move |(x, y)| Rectangle::new([(x, y - 7), (x + 12, y + 5)], style.filled()));
+    }
+    let note_x = x_range.start + (x_range.end - x_range.start) * 0.02;
+    for (gap, title) in gaps.iter().zip(&lang_list) {
+        let (x, y) = relative(
+            &root,
+            chart.backend_coord(&(note_x, SegmentValue::CenterOf(title))),
+        );
+        root.draw(&Text::new(
+            gap.note.clone(),
+            (x, y - 6),
+            ("sans-serif", 12).into_font().color(&RED),
+        ))?;
+    }
+    if let Some(position) = opts.legend.position() {
+        chart
+            .configure_series_labels()
+            .position(position)
+            .label_font(("sans-serif", 12).into_font().color(&foreground))
+            .background_style(opts.theme.background().filled())
+            .border_style(foreground.mix(0.5))
+            .legend_area_size(22)
+            .draw()?;
+    }
+    Ok(())
+}
+
+/// Draws the histograms or the CDF curves of all the languages on the same axes: a color per
+/// language, the first scenario solid and the others dashed.
+fn overlay<DB: DrawingBackend>(
+    series: &[Series],
+    gaps: &[Gap],
+    host: &HostInfo,
+    opts: &ChartOptions,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    // The best languages are the first in the legend
+    let titles: Vec<String> = sorted_titles(series, opts.sort).into_iter().rev().collect();
+    let first_scenario = series.iter().map(|x| x.scenario_idx).min().unwrap_or(0);
+    let scaled: Vec<(&Series, Vec<f64>)> = series
+        .iter()
+        .sorted_by_key(|x| {
+            let position = titles.iter().position(|title| title == x.title);
+            (position, x.scenario_idx)
+        })
+        .map(|x| {
+            let values = stats::sorted(
+                stats::latencies(x.samples)
+                    .into_iter()
+                    .map(|value| scale(value, opts.log_scale)),
+            );
+            (x, values)
+        })
+        .collect();
+    let start = scaled
+        .iter()
+        .filter_map(|x| x.1.first())
+        .fold(f64::MAX, |a, b| a.min(*b)) as f32;
+    let x_range = x_range(start..tail_limit(series, opts.log_scale), opts.log_scale);
+    let bin_width = (x_range.end - x_range.start) as f64 / HISTOGRAM_BINS as f64;
+    let curves: Vec<Vec<(f32, f32)>> = scaled
+        .iter()
+        .map(|(_, values)| match opts.kind {
+            ChartType::Cdf => (0..=1000)
+                .map(|x| {
+                    let pct = x as f64 / 10.0;
+                    (stats::percentile(values, pct) as f32, pct as f32)
+                })
+                .filter(|x| x.0 <= x_range.end)
+                .collect(),
+            _ => {
+                let mut counts = vec![0; HISTOGRAM_BINS];
+                for value in values {
+                    let bin = ((value - x_range.start as f64) / bin_width) as usize;
+                    if let Some(count) = counts.get_mut(bin) {
+                        *count += 1;
+                    }
+                }
+                // The steps of the histogram outline
+                counts
+                    .into_iter()
+                    .enumerate()
+                    .flat_map(|(bin, count)| {
+                        let left = x_range.start + (bin as f64 * bin_width) as f32;
+                        let right = x_range.start + ((bin + 1) as f64 * bin_width) as f32;
+                        let pct = 100.0 * count as f32 / values.len() as f32;
+                        [(left, pct), (right, pct)]
+                    })
+                    .collect()
+            }
+        })
+        .collect();
+    let max_pct = curves
+        .iter()
+        .flatten()
+        .map(|x| x.1)
+        .fold(0.0, f32::max)
+        .max(1.0);
+
+    let foreground = opts.theme.foreground();
+    let root = frame(backend, host, opts)?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.clone(), 0f32..max_pct * 1.05)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(x_desc(opts))
+        .y_desc(if opts.kind == ChartType::Cdf {
+            "Requests completed, %"
+        } else {
+            "Requests, %"
+        })
+        .x_label_formatter(&|x| x_label(*x, opts))
+        .axis_style(foreground)
+        .label_style(("sans-serif", 12).into_font().color(&foreground))
+        .axis_desc_style(("sans-serif", 14).into_font().color(&foreground))
+        .bold_line_style(opts.theme.grid())
+        .light_line_style(opts.theme.background())
+        .draw()?;
+
+    for ((x, _), curve) in scaled.iter().zip(curves) {
+        let lang_idx = titles
+            .iter()
+            .position(|title| title == x.title)
+            .unwrap_or(0);
+        let style = Palette99::pick(lang_idx).stroke_width(2);
+        let label = format!("{}, {}", x.title, x.scenario);
+        if x.scenario_idx == first_scenario {
+            chart
+                .draw_series(LineSeries::new(curve, style))?
+                .label(label)
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 12, y)], style));
+        } else {
+            chart
+                .draw_series(DashedLineSeries::new(curve, 6, 4, style))?
+                .label(label)
+                .legend(move |(x, y)| DashedPathElement::new([(x, y), (x + 12, y)], 3, 2, style));
+        }
+    }
+    // The failed languages are listed in the legend
+    for gap in gaps {
+        chart
+            .draw_series(std::iter::empty::<Circle<(f32, f32), i32>>())?
+            .label(format!("{}: {}", gap.title, gap.note))
+            .legend(|(x, y)| Cross::new((x + 6, y), 4, RED));
+    }
+    if let Some(position) = opts.legend.position() {
+        chart
+            .configure_series_labels()
+            .position(position)
+            .label_font(("sans-serif", 12).into_font().color(&foreground))
+            .background_style(opts.theme.background().mix(0.8).filled())
+            .border_style(foreground.mix(0.5))
+            .legend_area_size(22)
+            .draw()?;
+    }
+    Ok(())
+}
+
+/// The throughput metric of a panel of the throughput chart.
+type Metric = (&'static str, fn(&Series) -> Option<f64>);
+
+/// Returns the panels of the throughput chart: the achieved throughput, and the efficiency if the
+/// resources of the servers have been sampled.
+fn throughput_metrics(series: &[Series]) -> Vec<Metric> {
+    let mut metrics: Vec<Metric> = vec![("Requests/s", |x| Some(stats::rps(x.samples)))];
+    if series.iter().any(|x| x.usage.is_some()) {
+        metrics.push(("Requests per CPU-second", |x| {
+            x.usage?.requests_per_cpu_second(x.samples.len())
+        }));
+        metrics.push(("Requests/s per MB of RSS", |x| {
+            x.usage?.rps_per_mb(stats::rps(x.samples))
+        }));
+    }
+    metrics
+}
+
+/// Returns the dimensions of the throughput chart, a panel of the chart size per metric.
+pub fn throughput_size(series: &[Series], opts: &ChartOptions) -> (u32, u32) {
+    let (width, height) = opts.size.pixels();
+    (width * throughput_metrics(series).len() as u32, height)
+}
+
+/// Draws the horizontal bars of the throughput of every language and scenario, and of the
+/// efficiency (requests per CPU-second and per MB of the resident memory) if it's been measured.
+pub fn throughput<DB: DrawingBackend>(
+    series: &[Series],
+    gaps: &[Gap],
+    host: &HostInfo,
+    opts: &ChartOptions,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let lang_list: Vec<String> = gaps
+        .iter()
+        .map(|x| x.title.clone())
+        .chain(sorted_titles(series, opts.sort))
+        .collect();
+    let scenarios: Vec<(&str, usize)> = series
+        .iter()
+        .map(|x| (x.scenario, x.scenario_idx))
+        .unique()
+        .sorted_by_key(|x| x.1)
+        .collect();
+    // Without the measured languages only the gaps are drawn
+    let count = scenarios.len().max(1) as i32;
+
+    let foreground = opts.theme.foreground();
+    let root = frame(backend, host, opts)?;
+    let metrics = throughput_metrics(series);
+    let panels = root.split_evenly((1, metrics.len()));
+    for (panel_idx, ((heading, metric), panel)) in metrics.iter().zip(&panels).enumerate() {
+        let values: Vec<Option<f64>> = series.iter().map(metric).collect();
+        let max = values.iter().flatten().copied().fold(0.0, f64::max);
+        let panel = panel.titled(heading, ("sans-serif", 14).into_font().color(&foreground))?;
+        let mut chart = ChartBuilder::on(&panel)
+            .margin_right(20)
+            .x_label_area_size(40)
+            .y_label_area_size(100)
+            .build_cartesian_2d(0.0..(max * 1.2).max(1.0), lang_list[..].into_segmented())?;
+        chart
+            .configure_mesh()
+            .y_labels(lang_list.len())
+            .x_labels(4)
+            .axis_style(foreground)
+            .label_style(("sans-serif", 12).into_font().color(&foreground))
+            .bold_line_style(opts.theme.grid())
+            .light_line_style(opts.theme.background())
+            .draw()?;
+
+        let row_height =
+            chart.plotting_area().dim_in_pixel().1 as i32 / lang_list.len().max(1) as i32;
+        let bar = (row_height * 3 / 5 / count).clamp(4, 20);
+        let font = ("sans-serif", 11).into_font().color(&foreground);
+        for (position, (scenario, idx)) in scenarios.iter().enumerate() {
+            let style = scenario_color(*idx);
+            // The bars of the scenarios are stacked around the center of the language row
+            let offset = (position as i32 * 2 - (count - 1)) * bar / 2;
+            for (x, value) in series.iter().zip(&values) {
+                let Some(title) = lang_list.iter().find(|title| *title == x.title) else {
+                    continue;
+                };
+                if x.scenario != *scenario {
+                    continue;
+                }
+                let (left, y) = relative(
+                    &panel,
+                    chart.backend_coord(&(0.0, SegmentValue::CenterOf(title))),
+                );
+                let y = y + offset;
+                match value {
+                    Some(value) => {
+                        let (right, _) = relative(
+                            &panel,
+                            chart.backend_coord(&(*value, SegmentValue::CenterOf(title))),
+                        );
+                        panel.draw(&Rectangle::new(
+                            [(left, y - bar / 2), (right.max(left + 1), y + bar / 2)],
+                            style.filled(),
+                        ))?;
+                        panel.draw(&Text::new(
+                            format!("{value:.0}"),
+                            (right + 4, y - 5),
+                            font.clone(),
+                        ))?;
+                    }
+                    None => {
+                        panel.draw(&Text::new("no data", (left + 4, y - 5), font.clone()))?;
+                    }
+                }
+            }
+            // Only for the legend
+            if panel_idx == 0 {
+                chart
+                    .draw_series(std::iter::empty::<Circle<(f64, SegmentValue<&String>), i32>>())?
+                    .label(*scenario)
+                    .legend(move |(x, y)| {
+                        Rectangle::new([(x, y - 7), (x + 12, y + 5)], style.filled())
+                    });
+            }
+        }
+        for (gap, title) in gaps.iter().zip(&lang_list) {
+            let (x, y) = relative(
+                &panel,
+                chart.backend_coord(&(0.0, SegmentValue::CenterOf(title))),
+            );
+            panel.draw(&Text::new(
+                gap.note.clone(),
+                (x + 4, y - 6),
+                ("sans-serif", 12).into_font().color(&RED),
+            ))?;
+        }
+        if let (0, Some(position)) = (panel_idx, opts.legend.position()) {
+            chart
+                .configure_series_labels()
+                .position(position)
+                .label_font(("sans-serif", 12).into_font().color(&foreground))
+                .background_style(opts.theme.background().filled())
+                .border_style(foreground.mix(0.5))
+                .legend_area_size(22)
+                .draw()?;
+        }
+    }
+    Ok(())
+}
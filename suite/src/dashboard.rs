@@ -0,0 +1,255 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::logging;
+use crate::resources::{self, Usage};
+use crate::stats::{self, Sample};
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+const BAR_WIDTH: usize = 30;
+
+/// The throughput and the latency of the last completed batch of requests.
+struct Latest {
+    rps: f64,
+    p50: f64,
+    p99: f64,
+}
+
+struct Server {
+    pid: u32,
+    /// The previous sample, to get the CPU usage between the refreshes.
+    last: Option<(Instant, Usage)>,
+    cpu_percent: Option<f64>,
+    rss_mb: Option<f64>,
+}
+
+struct State {
+    total: usize,
+    done: usize,
+    started: Instant,
+    target_started: Instant,
+    /// The time spent on the measured targets (excluding the resumed ones), for the ETA.
+    measured: Duration,
+    measured_count: u32,
+    context: String,
+    latest: Option<Latest>,
+    server: Option<Server>,
+    /// The number of the lines drawn last time, which are redrawn in place.
+    lines: usize,
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+impl State {
+    fn eta(&self) -> Option<Duration> {
+        let remaining = self.total.saturating_sub(self.done) as u32;
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+        let average = self.measured.checked_div(self.measured_count)?;
+        // The current target is partially done already
+        Some((average * remaining).saturating_sub(self.target_started.elapsed().min(average)))
+    }
+
+    fn render(&self) -> Vec<String> {
+        let filled = (BAR_WIDTH * self.done).checked_div(self.total).unwrap_or(0);
+        let eta = self
+            .eta()
+            .map_or_else(|| String::from("-"), format_duration);
+        let mut lines = vec![
+            format!(
+                "Progress [{}{}] {}/{}  elapsed {}  ETA {eta}",
+                "#".repeat(filled),
+                ".".repeat(BAR_WIDTH - filled),
+                self.done,
+                self.total,
+                format_duration(self.started.elapsed())
+            ),
+            format!(
+                "Current  {} ({})",
+                if self.context.is_empty() {
+                    "-"
+                } else {
+                    &self.context
+                },
+                format_duration(self.target_started.elapsed())
+            ),
+        ];
+        lines.push(match &self.latest {
+            Some(x) => format!(
+                "Latest   {:.0} req/s  p50 {:.3} ms  p99 {:.3} ms",
+                x.rps, x.p50, x.p99
+            ),
+            None => String::from("Latest   -"),
+        });
+        lines.push(match &self.server {
+            Some(server) => format!(
+                "Server   pid {}  CPU {}  RSS {}",
+                server.pid,
+                server
+                    .cpu_percent
+                    .map_or_else(|| String::from("-"), |x| format!("{x:.0}%")),
+                server
+                    .rss_mb
+                    .map_or_else(|| String::from("-"), |x| format!("{x:.1} MB"))
+            ),
+            None => String::from("Server   -"),
+        });
+        lines
+    }
+
+    /// Erases the dashboard, so the other output could be printed in its place.
+    fn clear(&mut self, out: &mut impl Write) {
+        if self.lines > 0 {
+            let _ = write!(out, "\x1b[{}A\x1b[J", self.lines);
+            self.lines = 0;
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) {
+        self.clear(out);
+        let lines = self.render();
+        for line in &lines {
+            let _ = writeln!(out, "{line}");
+        }
+        self.lines = lines.len();
+        let _ = out.flush();
+    }
+
+    fn sample_server(&mut self) {
+        let Some(server) = self.server.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        let usage = resources::tree_usage(server.pid);
+        if let (Some((time, last)), Some(usage)) = (server.last, usage) {
+            let elapsed = now.duration_since(time).as_secs_f64();
+            server.cpu_percent = Some(100.0 * (usage.cpu_seconds - last.cpu_seconds) / elapsed);
+        }
+        server.rss_mb = usage.map(|x| x.rss_mb);
+        server.last = usage.map(|x| (now, x));
+    }
+}
+
+/// Shows the dashboard with the given number of the targets, if the output is a terminal.
+pub fn start(total: usize) {
+    if !io::stdout().is_terminal() {
+        logging::log(
+            "The output is not a terminal, showing the plain log instead of the dashboard",
+        );
+        return;
+    }
+    let now = Instant::now();
+    *STATE.lock().unwrap() = Some(State {
+        total,
+        done: 0,
+        started: now,
+        target_started: now,
+        measured: Duration::ZERO,
+        measured_count: 0,
+        context: String::new(),
+        latest: None,
+        server: None,
+        lines: 0,
+    });
+    thread::spawn(|| loop {
+        thread::sleep(REFRESH_INTERVAL);
+        // The tags are taken before locking the state, as the logging draws the dashboard too
+        let context = logging::context();
+        let mut state = STATE.lock().unwrap();
+        let Some(state) = state.as_mut() else {
+            break;
+        };
+        state.context = context;
+        state.sample_server();
+        state.draw(&mut io::stdout());
+    });
+}
+
+/// Removes the dashboard, leaving the plain log.
+pub fn stop() {
+    if let Some(mut state) = STATE.lock().unwrap().take() {
+        state.clear(&mut io::stdout());
+    }
+}
+
+pub fn is_active() -> bool {
+    STATE.lock().unwrap().is_some()
+}
+
+/// Sets the server process to monitor.
+pub fn set_server(pid: Option<u32>) {
+    if let Some(state) = STATE.lock().unwrap().as_mut() {
+        state.server = pid.map(|pid| Server {
+            pid,
+            last: None,
+            cpu_percent: None,
+            rss_mb: None,
+        });
+    }
+}
+
+/// Shows the results of a batch of requests.
+pub fn record(samples: &[Sample]) {
+    if let Some(state) = STATE.lock().unwrap().as_mut() {
+        let latencies = stats::sorted(stats::latencies(samples).into_iter());
+        state.latest = Some(Latest {
+            rps: stats::rps(samples),
+            p50: stats::percentile(&latencies, 50.0),
+            p99: stats::percentile(&latencies, 99.0),
+        });
+    }
+}
+
+/// Marks the current target as done, the duration is only given if it has been measured.
+pub fn target_done(duration: Option<Duration>) {
+    if let Some(state) = STATE.lock().unwrap().as_mut() {
+        state.done += 1;
+        state.target_started = Instant::now();
+        state.latest = None;
+        if let Some(duration) = duration {
+            state.measured += duration;
+            state.measured_count += 1;
+        }
+    }
+}
+
+/// Prints the line above the dashboard (or just prints it if there is no dashboard).
+pub fn println(line: &str) {
+    let mut state = STATE.lock().unwrap();
+    match state.as_mut() {
+        Some(state) => {
+            let mut out = io::stdout().lock();
+            state.clear(&mut out);
+            let _ = writeln!(out, "{line}");
+            state.draw(&mut out);
+        }
+        None => println!("{line}"),
+    }
+}
+
+/// Prints the line of the tool output above the dashboard.
+pub fn eprintln(line: &str) {
+    let mut state = STATE.lock().unwrap();
+    match state.as_mut() {
+        Some(state) => {
+            let mut out = io::stdout().lock();
+            state.clear(&mut out);
+            let _ = out.flush();
+            eprintln!("{line}");
+            state.draw(&mut out);
+        }
+        None => eprintln!("{line}"),
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use crate::logging::log;
+use crate::probes::{self, Status};
+use crate::Cmd;
+
+/// The tools required by the runner itself (not needed to only build the servers).
+pub const RUNNER_REQUIREMENTS: [&str; 2] = ["hey", "lsof"];
+
+/// Checks the tools required by the languages and the `common` ones, and returns the unmet
+/// requirements for every language (empty if the language is runnable).
+pub fn check(
+    lang_cmds: &BTreeMap<&str, Cmd>,
+    langs: &[String],
+    common: &[&str],
+) -> BTreeMap<String, Vec<String>> {
+    let selected: Vec<(&str, &Cmd)> = lang_cmds
+        .iter()
+        .filter(|(lang, _)| langs.iter().any(|x| x == *lang))
+        .map(|(lang, cmd)| (*lang, cmd))
+        .collect();
+    let mut names: Vec<&str> = common.to_vec();
+    names.extend(
+        selected
+            .iter()
+            .flat_map(|(_, cmd)| cmd.requires.iter().copied()),
+    );
+    let requirements: BTreeMap<&str, probes::ProbeFn> = probes::requirements()
+        .into_iter()
+        .filter(|(name, _)| names.contains(name))
+        .collect();
+
+    log("Checking the required tools...");
+    let outcomes = probes::run_all(&requirements);
+    for (name, outcome) in &outcomes {
+        match (&outcome.version, &outcome.detail) {
+            (Some(version), _) => log(&format!("  {name}: {} ({version})", outcome.status)),
+            (_, Some(detail)) => log(&format!("  {name}: {} ({detail})", outcome.status)),
+            _ => log(&format!("  {name}: {}", outcome.status)),
+        }
+    }
+
+    selected
+        .into_iter()
+        .map(|(lang, cmd)| {
+            let missing = common
+                .iter()
+                .chain(cmd.requires)
+                .filter(|name| {
+                    outcomes
+                        .get(*name)
+                        .is_none_or(|outcome| outcome.status != Status::Ok)
+                })
+                .map(|name| name.to_string())
+                .collect();
+            (lang.to_string(), missing)
+        })
+        .collect()
+}
+
+/// Logs the runnable and unavailable languages, and returns the number of the unavailable ones.
+pub fn summary(
+    lang_cmds: &BTreeMap<&str, Cmd>,
+    availability: &BTreeMap<String, Vec<String>>,
+) -> usize {
+    let mut unavailable = 0;
+    for (lang, missing) in availability {
+        let title = lang_cmds.get(lang.as_str()).map_or("", |x| x.title);
+        if missing.is_empty() {
+            log(&format!("{lang} ({title}): runnable"));
+        } else {
+            unavailable += 1;
+            log(&format!(
+                "{lang} ({title}): unavailable, missing {}",
+                missing.join(", ")
+            ));
+        }
+    }
+    log(&format!(
+        "{} of {} languages are runnable",
+        availability.len() - unavailable,
+        availability.len()
+    ));
+    unavailable
+}
@@ -1,54 +1,236 @@
 use std::error::Error;
 use std::fmt;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus};
+use std::time::Duration;
 
-#[derive(Debug)]
-pub struct ProcessError {
-    code: Option<i32>,
+/// The number of the last stderr lines kept in the errors.
+pub const STDERR_TAIL_LINES: usize = 10;
+
+/// Where the failed command has been run.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub command: String,
+    pub cwd: PathBuf,
+    pub target: Option<String>,
 }
 
-impl ProcessError {
-    pub fn new(code: Option<i32>) -> ProcessError {
-        ProcessError { code }
+impl Context {
+    pub fn new(cmd: &Command) -> Context {
+        let command = std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|x| {
+                let arg = x.to_string_lossy();
+                if arg.contains(char::is_whitespace) {
+                    format!("'{arg}'")
+                } else {
+                    arg.into_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let cwd = match cmd.get_current_dir() {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir().unwrap_or_default(),
+        };
+        Context {
+            command,
+            cwd,
+            target: None,
+        }
     }
 }
 
-impl fmt::Display for ProcessError {
+impl fmt::Display for Context {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.code)
+        if let Some(target) = &self.target {
+            write!(f, "[{target}] ")?;
+        }
+        write!(f, "`{}` in {}", self.command, self.cwd.display())
     }
 }
 
-impl Error for ProcessError {}
-
 #[derive(Debug)]
-pub struct PidError {}
+pub enum SuiteError {
+    /// The command couldn't be started.
+    Spawn {
+        context: Context,
+        error: std::io::Error,
+    },
+    /// The command exited unsuccessfully.
+    Process {
+        context: Context,
+        status: ExitStatus,
+        stderr: String,
+    },
+    /// The command has been killed as it didn't finish in time.
+    Timeout {
+        context: Context,
+        timeout: Duration,
+    },
+    /// The server didn't write its pid file in time.
+    Pid {
+        context: Context,
+        attempts: u32,
+    },
+    /// The pid file of the server doesn't contain a pid.
+    InvalidPid {
+        context: Context,
+        content: String,
+    },
+    /// No requests of the scenario have been completed.
+    NoSamples {
+        scenario: String,
+    },
+    UnknownFileType {
+        file: String,
+    },
+    ValueIsEmpty {
+        what: String,
+    },
+    /// The language to run is neither a key, a glob nor a tag.
+    UnknownTarget {
+        target: String,
+        /// The known names close to the target.
+        suggestions: Vec<String>,
+    },
+    /// The targets and the exclusions (or the unavailable tools) leave no languages to run.
+    NoLanguages,
+}
 
-impl fmt::Display for PidError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Pid file not found")
+impl SuiteError {
+    pub fn spawn(cmd: &Command, error: std::io::Error) -> SuiteError {
+        SuiteError::Spawn {
+            context: Context::new(cmd),
+            error,
+        }
     }
-}
 
-impl Error for PidError {}
+    pub fn process(cmd: &Command, status: ExitStatus, stderr: &str) -> SuiteError {
+        SuiteError::Process {
+            context: Context::new(cmd),
+            status,
+            stderr: stderr_tail(stderr),
+        }
+    }
 
-#[derive(Debug)]
-pub struct UnknownFileTypeError {}
+    pub fn timeout(cmd: &Command, timeout: Duration) -> SuiteError {
+        SuiteError::Timeout {
+            context: Context::new(cmd),
+            timeout,
+        }
+    }
 
-impl fmt::Display for UnknownFileTypeError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Unknown file type")
+    pub fn pid(cmd: &Command, attempts: u32) -> SuiteError {
+        SuiteError::Pid {
+            context: Context::new(cmd),
+            attempts,
+        }
+    }
+
+    pub fn invalid_pid(cmd: &Command, content: &str) -> SuiteError {
+        SuiteError::InvalidPid {
+            context: Context::new(cmd),
+            content: content.to_string(),
+        }
     }
+
+    fn context_mut(&mut self) -> Option<&mut Context> {
+        match self {
+            SuiteError::Spawn { context, .. }
+            | SuiteError::Process { context, .. }
+            | SuiteError::Timeout { context, .. }
+            | SuiteError::Pid { context, .. }
+            | SuiteError::InvalidPid { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+}
+
+/// Sets the target name for the suite errors (other errors are returned as is).
+pub fn with_target(mut e: Box<dyn Error>, target: &str) -> Box<dyn Error> {
+    if let Some(context) = e.downcast_mut::<SuiteError>().and_then(|x| x.context_mut()) {
+        context.target = Some(target.to_string());
+    }
+    e
 }
 
-impl Error for UnknownFileTypeError {}
+/// Returns the exit code, or the signal that has terminated the process.
+fn describe_status(status: &ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
 
-#[derive(Debug)]
-pub struct ValueIsEmptyError {}
+    match (status.code(), status.signal()) {
+        (Some(code), _) => format!("exit code {code}"),
+        (None, Some(signal)) => format!("signal {signal}"),
+        _ => String::from("unknown status"),
+    }
+}
+
+pub fn stderr_tail(text: &str) -> String {
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+}
 
-impl fmt::Display for ValueIsEmptyError {
+impl fmt::Display for SuiteError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Expected some value, but got None")
+        match self {
+            SuiteError::Spawn { context, error } => {
+                write!(f, "{context} couldn't be started: {error}")
+            }
+            SuiteError::Process {
+                context,
+                status,
+                stderr,
+            } => {
+                write!(f, "{context} failed with {}", describe_status(status))?;
+                if !stderr.is_empty() {
+                    write!(f, ":\n{stderr}")?;
+                }
+                Ok(())
+            }
+            SuiteError::Timeout { context, timeout } => {
+                write!(f, "{context} timed out after {}s", timeout.as_secs())
+            }
+            SuiteError::Pid { context, attempts } => {
+                write!(f, "{context}: pid file not found after {attempts} attempts")
+            }
+            SuiteError::InvalidPid { context, content } => {
+                write!(
+                    f,
+                    "{context}: invalid pid file content '{}'",
+                    content.trim()
+                )
+            }
+            SuiteError::NoSamples { scenario } => {
+                write!(
+                    f,
+                    "No requests of the {scenario} scenario have been completed"
+                )
+            }
+            SuiteError::UnknownFileType { file } => write!(f, "Unknown file type: {file}"),
+            SuiteError::ValueIsEmpty { what } => {
+                write!(f, "Expected some value for {what}, but got None")
+            }
+            SuiteError::UnknownTarget {
+                target,
+                suggestions,
+            } => {
+                write!(f, "Unknown language or tag: {target}")?;
+                if !suggestions.is_empty() {
+                    write!(f, " (did you mean {}?)", suggestions.join(" or "))?;
+                }
+                Ok(())
+            }
+            SuiteError::NoLanguages => write!(f, "No languages to run"),
+        }
     }
 }
 
-impl Error for ValueIsEmptyError {}
+impl Error for SuiteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SuiteError::Spawn { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+
+const FOOTER_LINE_HEIGHT: i32 = 13;
+
+/// The description of the machine the benchmarks run on.
+#[derive(Clone, Serialize)]
+pub struct HostInfo {
+    pub cpu_model: String,
+    pub cores: usize,
+    pub mem_total_mb: u64,
+    pub kernel: String,
+    pub distro: String,
+}
+
+/// Returns the trimmed content of the file (e.g. in `/proc` or `/sys`), if it's readable.
+pub(crate) fn read(path: &str) -> Option<String> {
+    fs::read_to_string(path).ok().map(|x| x.trim().to_string())
+}
+
+/// Returns the `/proc/meminfo` value in megabytes.
+pub fn meminfo(key: &str) -> Option<u64> {
+    let content = read("/proc/meminfo")?;
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?;
+        let kb: u64 = value.trim().trim_end_matches(" kB").parse().ok()?;
+        Some(kb / 1024)
+    })
+}
+
+fn cpuinfo() -> (Option<String>, usize) {
+    let content = read("/proc/cpuinfo").unwrap_or_default();
+    let value = |line: &str| line.split_once(':').map(|x| x.1.trim().to_string());
+    let model = content
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(value);
+    let cores = content
+        .lines()
+        .filter(|line| line.starts_with("processor"))
+        .count();
+    (model, cores)
+}
+
+fn os_release() -> Option<String> {
+    let content = read("/etc/os-release")?;
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix("PRETTY_NAME=")?;
+        Some(value.trim_matches('"').to_string())
+    })
+}
+
+impl HostInfo {
+    pub fn collect() -> HostInfo {
+        let (model, cores) = cpuinfo();
+        let unknown = || String::from("unknown");
+        HostInfo {
+            cpu_model: model.unwrap_or_else(unknown),
+            cores: if cores > 0 {
+                cores
+            } else {
+                std::thread::available_parallelism().map_or(1, |x| x.get())
+            },
+            mem_total_mb: meminfo("MemTotal").unwrap_or_default(),
+            kernel: read("/proc/sys/kernel/osrelease").unwrap_or_else(unknown),
+            distro: os_release().unwrap_or_else(unknown),
+        }
+    }
+
+    fn memory(&self) -> String {
+        format!("{:.0}GB", self.mem_total_mb as f64 / 1024.0)
+    }
+
+    /// The short summary for the chart footers (split in lines to fit the narrow charts).
+    pub fn summary(&self) -> [String; 2] {
+        [
+            format!(
+                "CPU: {} ({} cores), Mem: {}",
+                self.cpu_model,
+                self.cores,
+                self.memory()
+            ),
+            format!("Linux {}, {}", self.kernel, self.distro),
+        ]
+    }
+
+    /// The environment description for the README.
+    pub fn asciidoc(&self) -> String {
+        format!(
+            "CPU: {} ({} cores), Mem: {}\n\nKernel: Linux {}\n\nOS: {}\n",
+            self.cpu_model,
+            self.cores,
+            self.memory(),
+            self.kernel,
+            self.distro
+        )
+    }
+
+    /// The description as the name-value pairs.
+    pub fn properties(&self) -> [(&'static str, String); 4] {
+        [
+            ("CPU", format!("{} ({} cores)", self.cpu_model, self.cores)),
+            ("Mem", self.memory()),
+            ("Kernel", format!("Linux {}", self.kernel)),
+            ("OS", self.distro.clone()),
+        ]
+    }
+
+    /// The description as `#`-prefixed comment lines for the text results.
+    pub fn comments(&self) -> String {
+        self.properties()
+            .iter()
+            .map(|(name, value)| format!("# {name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws the summary at the bottom of the area, and returns the rest of the area for the chart.
+    pub fn footer<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        color: &RGBColor,
+    ) -> Result<DrawingArea<DB, Shift>, Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        let lines = self.summary();
+        let footer_height = (lines.len() as i32 * FOOTER_LINE_HEIGHT + 4) as u32;
+        let height = root.dim_in_pixel().1;
+        let (upper, lower) = root.split_vertically(height.saturating_sub(footer_height));
+        let style = ("sans-serif", 11).into_font().color(&color.mix(0.6));
+        for (idx, line) in lines.iter().enumerate() {
+            lower.draw_text(line, &style, (5, 2 + idx as i32 * FOOTER_LINE_HEIGHT))?;
+        }
+        Ok(upper)
+    }
+}
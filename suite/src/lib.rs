@@ -0,0 +1,5 @@
+//! The modules shared by the benchmark runner and the version reporter.
+
+pub mod errors;
+pub mod probes;
+pub mod tables;
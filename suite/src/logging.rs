@@ -0,0 +1,158 @@
+use chrono::Local;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::dashboard;
+
+/// The verbosity of the console output, every level includes the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Only the warnings and the errors.
+    Quiet,
+    /// The progress of the benchmarks and the output of the tools.
+    Normal,
+    /// The details like the warmup batches and the process management.
+    Verbose,
+    /// Every executed command.
+    Trace,
+}
+
+struct Logger {
+    level: Level,
+    /// Gets all the messages regardless of the level.
+    file: Option<File>,
+}
+
+/// What is being done right now, prepended to every message.
+struct Tags {
+    target: Option<String>,
+    scenario: Option<&'static str>,
+    phase: Option<&'static str>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    level: Level::Normal,
+    file: None,
+});
+
+static TAGS: Mutex<Tags> = Mutex::new(Tags {
+    target: None,
+    scenario: None,
+    phase: None,
+});
+
+pub fn init(level: Level, file: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let file = file.map(File::create).transpose()?;
+    let mut logger = LOGGER.lock().unwrap();
+    logger.level = level;
+    logger.file = file;
+    Ok(())
+}
+
+pub fn enabled(level: Level) -> bool {
+    LOGGER.lock().unwrap().level >= level
+}
+
+pub fn set_target(target: Option<&str>) {
+    let mut tags = TAGS.lock().unwrap();
+    tags.target = target.map(String::from);
+    tags.scenario = None;
+    tags.phase = None;
+}
+
+pub fn set_scenario(scenario: Option<&'static str>) {
+    TAGS.lock().unwrap().scenario = scenario;
+}
+
+pub fn set_phase(phase: Option<&'static str>) {
+    TAGS.lock().unwrap().phase = phase;
+}
+
+/// Appends a `key=value` line to the log file, if any.
+fn append(logger: &mut Logger, tags: &Tags, label: &str, key: &str, text: &str) {
+    if let Some(file) = logger.file.as_mut() {
+        let fields = [
+            ("target", tags.target.as_deref()),
+            ("scenario", tags.scenario),
+            ("phase", tags.phase),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.map(|x| format!(" {name}={x:?}")))
+        .collect::<String>();
+        // Ignore the errors as the logging shouldn't stop the benchmarks
+        let _ = writeln!(
+            file,
+            "{} {label:5}{fields} {key}={text:?}",
+            Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+        );
+    }
+}
+
+impl Tags {
+    fn describe(&self) -> String {
+        [self.target.as_deref(), self.scenario, self.phase]
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Returns the current target, scenario and phase, e.g. `Go/index/warmup`.
+pub fn context() -> String {
+    TAGS.lock().unwrap().describe()
+}
+
+fn write(level: Level, label: &str, msg: &str) {
+    let tags = TAGS.lock().unwrap();
+    let mut logger = LOGGER.lock().unwrap();
+    append(&mut logger, &tags, label, "msg", msg);
+    if logger.level < level {
+        return;
+    }
+    let time = Local::now().format("%H:%M:%S");
+    let line = match tags.describe() {
+        context if context.is_empty() => format!("{time} {msg}"),
+        context => format!("{time} [{context}] {msg}"),
+    };
+    // The dashboard is locked after the logging, so it could read the tags in the meantime
+    drop(logger);
+    drop(tags);
+    dashboard::println(&line);
+}
+
+pub fn error(msg: &str) {
+    write(Level::Quiet, "ERROR", msg);
+}
+
+pub fn warn(msg: &str) {
+    write(Level::Quiet, "WARN", &format!("Warning: {msg}"));
+}
+
+pub fn log(msg: &str) {
+    write(Level::Normal, "INFO", msg);
+}
+
+pub fn verbose(msg: &str) {
+    write(Level::Verbose, "DEBUG", msg);
+}
+
+pub fn trace(msg: &str) {
+    write(Level::Trace, "TRACE", msg);
+}
+
+/// Passes a line of the tool output through (printed as is, but tagged in the log file).
+pub fn output(line: &str) {
+    let tags = TAGS.lock().unwrap();
+    let mut logger = LOGGER.lock().unwrap();
+    append(&mut logger, &tags, "INFO", "output", line);
+    if logger.level >= Level::Normal {
+        drop(logger);
+        drop(tags);
+        dashboard::eprintln(line);
+    }
+}
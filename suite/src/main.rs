@@ -1,33 +1,72 @@
 #[macro_use]
 extern crate clap;
 
-use chrono::prelude::*;
-
 use clap::Arg;
 
+use chrono::Local;
 use itertools::Itertools;
-use plotters::data::fitting_range;
 use plotters::prelude::*;
 
 use regex::Regex;
 use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod affinity;
+mod chart;
+mod dashboard;
+mod doctor;
+mod host;
+mod logging;
+mod prebuild;
+mod preflight;
+mod report;
+mod resources;
+mod results;
+mod scaling;
+mod scenarios;
+mod server_log;
+mod stats;
+mod targets;
+mod timeline;
+
+use suite::{errors, probes, tables};
 
-mod errors;
+use affinity::CpuSet;
+use chart::{ChartOptions, ChartType, Gap, Legend, Series, Size, SortKey, Theme};
+use errors::SuiteError;
+use host::HostInfo;
+use logging::{log, Level};
+use preflight::Snapshot;
+use report::Report;
+use resources::{Sampler, ServerUsage};
+use results::{Checkpoint, Failure, Measurement, Phase, Results, ScenarioRun};
+use scaling::ScalingPoint;
+use scenarios::Scenario;
+use server_log::ServerLog;
+use stats::Sample;
+use tables::Table;
+use targets::Selection;
 
 type UnitResult = Result<(), Box<dyn Error>>;
 type UnsignedResult = Result<u32, Box<dyn Error>>;
 
-const INDEX: &str = "Index URL Request";
-const PATTERN: &str = "Pattern URL Request";
-
 struct Cmd<'a> {
     title: &'a str,
-    build: Box<dyn Fn() -> UnitResult>,
-    run: Box<dyn Fn() -> UnsignedResult>,
+    /// The tools (see `probes::requirements`) needed to build and run the server.
+    requires: &'a [&'a str],
+    /// The groups the language could be selected by (see `targets::TAGS`).
+    tags: &'a [&'a str],
+    /// The files and directories the artifact is built from.
+    sources: &'a [&'a str],
+    /// The built server, if the language has the build step.
+    artifact: Option<&'a str>,
+    build: Box<dyn Fn() -> UnitResult + Sync>,
+    run: Box<dyn Fn(&Options) -> UnsignedResult>,
 }
 
 thread_local! {
@@ -45,17 +84,74 @@ thread_local! {
 }
 
 const ATTEMPTS: u32 = 30;
+const REQUESTS: u32 = 50000;
+const WARMUP_REQUESTS: u32 = 5000;
+const CONCURRENCY: u32 = 256;
+/// Seconds.
+const REQUEST_TIMEOUT: u32 = 10;
+
+type RunResult = Result<Measurement, (Phase, Box<dyn Error>)>;
+/// The samples, the warmup time and the server resources of a scenario.
+type BenchmarkResult = Result<(Vec<Sample>, Duration, Option<ServerUsage>), Box<dyn Error>>;
 
-fn log(msg: &str) {
-    let local = Local::now();
-    println!("{} {}", local.format("%H:%M:%S"), msg)
+#[derive(Clone)]
+struct Warmup {
+    tolerance: f64,
+    max_batches: u32,
+}
+
+#[derive(Clone)]
+struct Options {
+    warmup: Warmup,
+    server_cpus: Option<CpuSet>,
+    client_cpus: Option<CpuSet>,
+    /// The number of workers for the servers that support it (the others size themselves
+    /// by the CPU affinity).
+    workers: Option<usize>,
+    server_log: Option<ServerLog>,
+    /// The selected scenarios, in the order of the registry.
+    scenarios: Vec<&'static Scenario>,
+    /// Whether the resources used by the server are sampled during the measured runs.
+    sample_resources: bool,
+}
+
+impl Options {
+    /// Restricts the server to the first `cores` CPUs of its set.
+    fn restricted(&self, cpus: &CpuSet, cores: usize) -> Options {
+        Options {
+            server_cpus: Some(cpus.first(cores)),
+            workers: Some(cores),
+            ..self.clone()
+        }
+    }
+}
+
+/// Passes the stderr of the child through, keeping its content for the error reports.
+fn tee_stderr(child: &mut std::process::Child) -> std::thread::JoinHandle<String> {
+    let stderr = child.stderr.take();
+    std::thread::spawn(move || {
+        let mut content = String::new();
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                logging::output(&line);
+                content.push_str(&line);
+                content.push('\n');
+            }
+        }
+        content
+    })
 }
 
 fn exec(cmd: &mut Command) -> UnitResult {
-    let mut child = cmd.spawn()?;
+    let mut child = cmd
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SuiteError::spawn(cmd, e))?;
+    let stderr = tee_stderr(&mut child);
     let status = child.wait()?;
+    let stderr = stderr.join().unwrap_or_default();
     if !status.success() {
-        Err(Box::new(errors::ProcessError::new(status.code())))
+        Err(Box::new(SuiteError::process(cmd, status, &stderr)))
     } else {
         Ok(())
     }
@@ -66,27 +162,49 @@ fn pexec(cmd: &mut Command) -> UnitResult {
     exec(cmd)
 }
 
-fn pspawn(cmd: &mut Command) -> UnsignedResult {
+fn pspawn(cmd: &mut Command, opts: &Options) -> UnsignedResult {
     static PID_FILE: &str = ".pid";
 
+    if let Some(cpus) = &opts.server_cpus {
+        cpus.apply(cmd);
+    }
+    if let Some(server_log) = &opts.server_log {
+        let (stdout, stderr) = server_log.stdio()?;
+        cmd.stdout(stdout).stderr(stderr);
+    }
     log(&format!("{cmd:?}"));
     let pid_exists = Path::new(PID_FILE).try_exists()?;
     if pid_exists {
         fs::remove_file(PID_FILE)?;
     }
-    cmd.spawn()?;
-    print!("Waiting");
+    let mut child = cmd.spawn().map_err(|e| SuiteError::spawn(cmd, e))?;
+    logging::verbose("Waiting for the pid file");
+    let mut error = SuiteError::pid(cmd, ATTEMPTS);
     for _ in 0..ATTEMPTS {
+        if let Some(status) = child.try_wait()? {
+            return Err(Box::new(SuiteError::process(cmd, status, "")));
+        }
         if let Ok(content) = fs::read_to_string(PID_FILE) {
-            return Ok(content.parse().unwrap());
+            // The file could be read before the server has written it completely
+            match content.trim().parse() {
+                Ok(pid) => {
+                    if let Some(cpus) = affinity::allowed_cpus(pid) {
+                        logging::verbose(&format!("Server {pid} runs on CPUs {cpus}"));
+                    }
+                    return Ok(pid);
+                }
+                Err(_) => error = SuiteError::invalid_pid(cmd, &content),
+            }
         }
         std::thread::sleep(std::time::Duration::from_secs(1));
-        print!(".")
     }
-    Err(Box::new(errors::PidError {}))
+    // Ignore any errors as the process could be finished already
+    let _ = child.kill();
+    Err(Box::new(error))
 }
 
 fn kill(pid: u32) {
+    logging::trace(&format!("Killing {pid}"));
     // Ignore any errors as the process could be finished already
     let _ = exec(Command::new("kill").args([&pid.to_string()]));
 }
@@ -107,22 +225,53 @@ fn kill_processes() -> Result<bool, Box<dyn Error>> {
     Ok(found)
 }
 
-fn run_hey(additional: &[&str], capture: bool) -> Result<Option<String>, Box<dyn Error>> {
+fn kill_leftovers() {
+    loop {
+        match kill_processes() {
+            Ok(true) => continue, // kill until all died
+            Ok(false) => break,
+            Err(e) => {
+                logging::warn(&format!("unable to find the processes on the port ({e})"));
+                break;
+            }
+        }
+    }
+}
+
+fn run_hey(
+    requests: u32,
+    additional: &[&str],
+    capture: bool,
+    opts: &Options,
+) -> Result<Option<String>, Box<dyn Error>> {
     let stdout = if capture {
         Stdio::piped()
-    } else {
+    } else if logging::enabled(Level::Normal) && !dashboard::is_active() {
         Stdio::inherit()
+    } else {
+        Stdio::null()
     };
     let mut cmd = Command::new("hey");
     cmd.stdout(stdout)
-        .args(["-n", "50000", "-c", "256", "-t", "10"])
+        .args(["-n", &requests.to_string()])
+        .args(["-c", &CONCURRENCY.to_string()])
+        .args(["-t", &REQUEST_TIMEOUT.to_string()])
         .args(additional);
+    if let Some(cpus) = &opts.client_cpus {
+        cpus.apply(&mut cmd);
+    }
+    logging::trace(&format!("{cmd:?}"));
     if capture {
-        let child = cmd.spawn()?;
+        let mut child = cmd
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SuiteError::spawn(&cmd, e))?;
+        let stderr = tee_stderr(&mut child);
         let output = child.wait_with_output()?;
+        let stderr = stderr.join().unwrap_or_default();
         let status = output.status;
         if !status.success() {
-            Err(Box::new(errors::ProcessError::new(status.code())))
+            Err(Box::new(SuiteError::process(&cmd, status, &stderr)))
         } else {
             let content = String::from_utf8(output.stdout)?;
             Ok(Some(content))
@@ -133,132 +282,771 @@ fn run_hey(additional: &[&str], capture: bool) -> Result<Option<String>, Box<dyn
     }
 }
 
-fn run_benchmark(lang: &str, is_index: bool) -> Result<Vec<f64>, Box<dyn Error>> {
-    let mut url = String::from("http://127.0.0.1:3000/");
-    if !is_index {
-        url.push_str("greeting/hello");
+fn parse_csv(content: &str) -> Vec<Sample> {
+    CSV_PATTERN.with(|re| {
+        content
+            .split('\n')
+            .filter_map(|line| {
+                let captures = re.captures(line)?;
+                let response_time: f64 = captures.name("responseTime")?.as_str().parse().unwrap();
+                let offset: f64 = captures.name("offset")?.as_str().parse().unwrap();
+                let status: u16 = captures.name("statusCode")?.as_str().parse().unwrap();
+                Some(Sample {
+                    offset,
+                    latency: response_time * 1000.0,
+                    status,
+                })
+            })
+            .collect()
+    })
+}
+
+/// Runs short batches until the median latency of two consecutive batches
+/// differs by no more than the tolerance, or the batch limit is reached.
+fn warm_up(scenario: &Scenario, opts: &Options) -> Result<Duration, Box<dyn Error>> {
+    let url = scenario.url();
+    let warmup = &opts.warmup;
+    let started = Instant::now();
+    let mut previous: Option<f64> = None;
+    for batch in 1..=warmup.max_batches {
+        let samples = match run_hey(
+            WARMUP_REQUESTS,
+            &["-m", scenario.method, "-o", "csv", &url],
+            true,
+            opts,
+        )? {
+            Some(content) => parse_csv(&content),
+            None => vec![],
+        };
+        dashboard::record(&samples);
+        let values = stats::latencies(&samples);
+        if values.is_empty() {
+            continue;
+        }
+        let median = Quartiles::new(&values).median();
+        logging::verbose(&format!("Warmup batch {batch}: median {median:.3} ms"));
+        if let Some(last) = previous {
+            let change = (median - last).abs() / last.max(f64::EPSILON);
+            if change <= warmup.tolerance {
+                log(&format!(
+                    "Warmup converged after {batch} batches in {:.1}s (median {median:.3} ms)",
+                    started.elapsed().as_secs_f64()
+                ));
+                return Ok(started.elapsed());
+            }
+        }
+        previous = Some(median);
+    }
+    log(&format!(
+        "Warmup did not converge after {} batches in {:.1}s",
+        warmup.max_batches,
+        started.elapsed().as_secs_f64()
+    ));
+    Ok(started.elapsed())
+}
+
+fn run_benchmark(scenario: &Scenario, pid: u32, opts: &Options) -> BenchmarkResult {
+    let url = scenario.url();
+    logging::set_scenario(Some(scenario.name));
+
+    // A single request first, so a wrong response fails fast instead of being measured
+    logging::set_phase(Some("check"));
+    scenario.check(Duration::from_secs(REQUEST_TIMEOUT as u64))?;
+
+    // Warmup runs, until the latency is stable (JIT, caches, etc.)
+    logging::set_phase(Some("warmup"));
+    let warmup_time = warm_up(scenario, opts)?;
+
+    // First measured run, for UI
+    logging::set_phase(Some("load"));
+    log(&format!("{} {url}", scenario.method));
+    run_hey(REQUESTS, &["-m", scenario.method, &url], false, opts)?;
+
+    // Second measured run, for stats
+    let sampler = opts
+        .sample_resources
+        .then(|| Sampler::start(pid, opts.client_cpus.clone()));
+    let content = run_hey(
+        REQUESTS,
+        &["-m", scenario.method, "-o", "csv", &url],
+        true,
+        opts,
+    );
+    let usage = sampler.and_then(Sampler::stop);
+    let samples = content?.map_or_else(Vec::new, |x| parse_csv(&x));
+    // Every request has failed (e.g. the server has crashed under the load)
+    if samples.is_empty() {
+        return Err(Box::new(SuiteError::NoSamples {
+            scenario: scenario.name.to_string(),
+        }));
+    }
+    dashboard::record(&samples);
+    Ok((samples, warmup_time, usage))
+}
+
+fn measure(pid: u32, opts: &Options) -> Result<Measurement, Box<dyn Error>> {
+    let mut measurement = Measurement::default();
+    let mut warmup_time = Duration::ZERO;
+    for scenario in &opts.scenarios {
+        let (samples, warmup, usage) = run_benchmark(scenario, pid, opts)?;
+        warmup_time += warmup;
+        measurement.scenarios.push(ScenarioRun {
+            scenario: scenario.name.to_string(),
+            samples,
+            usage,
+        });
+    }
+    logging::set_scenario(None);
+    logging::set_phase(None);
+    log(&format!(
+        "Total warmup time: {:.1}s",
+        warmup_time.as_secs_f64()
+    ));
+    Ok(measurement)
+}
+
+fn run(lang_cmd: &Cmd, opts: &Options) -> RunResult {
+    logging::set_phase(Some("start"));
+    let pid = (lang_cmd.run)(opts).map_err(|e| (Phase::Start, e))?;
+    dashboard::set_server(Some(pid));
+    let offset = opts.server_log.as_ref().map_or(0, |x| x.offset());
+    let result = measure(pid, opts);
+    logging::verbose(&format!("Killing {pid} process tree..."));
+    kill(pid);
+    dashboard::set_server(None);
+    let mut measurement = result.map_err(|e| (Phase::Load, e))?;
+    measurement.server_errors = opts
+        .server_log
+        .as_ref()
+        .map_or(0, |x| x.count_errors(offset));
+    if measurement.server_errors > 0 {
+        logging::warn(&format!(
+            "{} error lines in the server output during the measurement",
+            measurement.server_errors
+        ));
     }
+    Ok(measurement)
+}
 
-    // First run, for JIT
-    run_hey(&["-o", "csv", &url], true)?;
+/// Runs the language on 1, 2, 4, ... cores, and returns the results of the run on all cores.
+fn run_scaling(
+    lang_cmd: &Cmd,
+    opts: &Options,
+    cpus: &CpuSet,
+    points: &mut Vec<ScalingPoint>,
+) -> RunResult {
+    let mut last = None;
+    for cores in scaling::core_counts(cpus.len()) {
+        kill_leftovers();
+        log(&format!("Running on {cores} cores"));
+        let measurement = run(lang_cmd, &opts.restricted(cpus, cores))?;
+        points.push(ScalingPoint {
+            title: String::from(lang_cmd.title),
+            cores,
+            rps: measurement
+                .scenarios
+                .iter()
+                .map(|x| (x.scenario.clone(), stats::rps(&x.samples)))
+                .collect(),
+        });
+        last = Some(measurement);
+    }
+    Ok(last.unwrap_or_default())
+}
 
-    // Second run, for UI
-    println!("[{lang}] {url}");
-    run_hey(&[&url], false)?;
+/// Returns the samples of every language and scenario for the charts.
+fn chart_series<'a>(results: &'a Results) -> Vec<Series<'a>> {
+    results
+        .measured
+        .iter()
+        .flat_map(|(title, measurement)| {
+            measurement.scenarios.iter().map(move |run| {
+                let (scenario_idx, scenario) = scenarios::describe(&run.scenario);
+                Series {
+                    title,
+                    scenario,
+                    scenario_idx,
+                    samples: &run.samples,
+                    usage: run.usage,
+                }
+            })
+        })
+        .collect()
+}
 
-    // Third run, for stats
-    if let Some(content) = run_hey(&["-o", "csv", &url], true).unwrap() {
-        CSV_PATTERN.with(|re| {
-            let values = content
-                .split('\n')
-                .filter_map(|line| {
-                    let mut result = None;
-                    if let Some(captures) = re.captures(line) {
-                        if let Some(m) = captures.name("responseTime") {
-                            let double_value: f64 = m.as_str().parse().unwrap();
-                            result = Some(double_value * 1000.0)
-                        }
-                    }
-                    result
-                })
-                .collect();
-            Ok(values)
+fn chart_gaps(failures: &[Failure]) -> Vec<Gap> {
+    failures
+        .iter()
+        .map(|x| Gap {
+            title: x.title.clone(),
+            note: format!("failed ({})", x.phase),
         })
-    } else {
-        Ok(vec![])
+        .collect()
+}
+
+/// Returns the format of the additional charts: PNG for the PNG output, SVG otherwise.
+fn image_ext(file: &str) -> &'static str {
+    match Path::new(file).extension().and_then(|x| x.to_str()) {
+        Some("png") => "png",
+        _ => "svg",
     }
 }
 
-fn run(lang_cmd: &Cmd, verbose: bool) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
-    (lang_cmd.build)()?;
-    let pid = (lang_cmd.run)()?;
-    let index_values = run_benchmark(lang_cmd.title, true)?;
-    let pattern_values = run_benchmark(lang_cmd.title, false)?;
-    if verbose {
-        log(&format!("Killing {pid} process tree..."));
+/// Returns the path next to the main output file, e.g. `result-scaling.svg` for `result.svg`.
+fn sibling(file: &str, suffix: &str) -> PathBuf {
+    let path = Path::new(file);
+    let stem = path
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .unwrap_or("result");
+    path.with_file_name(format!("{stem}-{suffix}"))
+}
+
+fn check_affinity(opts: &Options) {
+    let describe = |cpus: &Option<CpuSet>| {
+        cpus.as_ref()
+            .map_or_else(|| String::from("any"), |x| x.to_string())
+    };
+    if opts.server_cpus.is_none() && opts.client_cpus.is_none() {
+        return;
     }
-    kill(pid);
-    Ok((index_values, pattern_values))
+    log(&format!(
+        "CPU assignment: server {}, load generator {}",
+        describe(&opts.server_cpus),
+        describe(&opts.client_cpus)
+    ));
+    let overlap = match (&opts.server_cpus, &opts.client_cpus) {
+        (Some(server), Some(client)) => server.overlaps(client),
+        _ => true,
+    };
+    if overlap {
+        logging::warn("the server and the load generator CPU sets overlap");
+    }
+}
+
+fn timeline_series(measurement: &Measurement) -> Vec<(usize, &str, Vec<timeline::Point>)> {
+    measurement
+        .scenarios
+        .iter()
+        .map(|x| {
+            let (idx, title) = scenarios::describe(&x.scenario);
+            (idx, title, timeline::series(&x.samples))
+        })
+        .collect()
+}
+
+/// Saves the timeline chart next to the main output file, e.g. `result-timeline-go.svg`
+/// (SVG is used if the main output is not an image).
+fn draw_timeline(
+    file: &str,
+    host: &HostInfo,
+    lang: &str,
+    title: &str,
+    measurement: &Measurement,
+    theme: Theme,
+) -> UnitResult {
+    let ext = image_ext(file);
+    let timeline_file = sibling(file, &format!("timeline-{lang}.{ext}"));
+    let scenarios = timeline_series(measurement);
+    log(&format!("Saving {}", timeline_file.display()));
+    match ext {
+        "svg" => timeline::draw_timeline(
+            title,
+            &scenarios,
+            host,
+            theme,
+            SVGBackend::new(&timeline_file, (800, 600)),
+        ),
+        _ => timeline::draw_timeline(
+            title,
+            &scenarios,
+            host,
+            theme,
+            BitMapBackend::new(&timeline_file, (800, 600)),
+        ),
+    }
+}
+
+/// Saves the throughput and efficiency chart next to the main output file, e.g.
+/// `result-throughput.svg` (SVG is used if the main output is not an image).
+fn draw_throughput(
+    file: &str,
+    host: &HostInfo,
+    chart_opts: &ChartOptions,
+    results: &Results,
+) -> UnitResult {
+    let ext = image_ext(file);
+    let throughput_file = sibling(file, &format!("throughput.{ext}"));
+    let series = chart_series(results);
+    let size = chart::throughput_size(&series, chart_opts);
+    log(&format!("Saving {}", throughput_file.display()));
+    match ext {
+        "svg" => chart::throughput(
+            &series,
+            &chart_gaps(&results.failures),
+            host,
+            chart_opts,
+            SVGBackend::new(&throughput_file, size),
+        ),
+        _ => chart::throughput(
+            &series,
+            &chart_gaps(&results.failures),
+            host,
+            chart_opts,
+            BitMapBackend::new(&throughput_file, size),
+        ),
+    }
+}
+
+/// Saves the AsciiDoc description of the host for the README next to the main output file,
+/// e.g. `result-environment.adoc`.
+fn save_environment(file: &str, host: &HostInfo) -> UnitResult {
+    let environment_file = sibling(file, "environment.adoc");
+    log(&format!("Saving {}", environment_file.display()));
+    fs::write(&environment_file, host.asciidoc())?;
+    Ok(())
+}
+
+/// Checks the host before the benchmarks, and saves the snapshot into the run directory,
+/// e.g. `results/20240101-120000/preflight.json` (a resumed session saves its own one next to it,
+/// e.g. `preflight-20240101-130000.json`).
+fn run_preflight(
+    run_dir: &Path,
+    host: &HostInfo,
+    strict: bool,
+) -> Result<Snapshot, Box<dyn Error>> {
+    log("Running pre-flight checks...");
+    let snapshot = preflight::check(host);
+    for warning in &snapshot.warnings {
+        logging::warn(warning);
+    }
+    let mut snapshot_file = run_dir.join("preflight.json");
+    if snapshot_file.exists() {
+        snapshot_file = run_dir.join(format!(
+            "preflight-{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    }
+    log(&format!("Saving {}", snapshot_file.display()));
+    fs::write(&snapshot_file, serde_json::to_string_pretty(&snapshot)?)?;
+    if strict && !snapshot.warnings.is_empty() {
+        return Err(format!(
+            "Pre-flight checks failed with {} warnings (--strict)",
+            snapshot.warnings.len()
+        )
+        .into());
+    }
+    Ok(snapshot)
 }
 
-fn draw<DB: DrawingBackend>(dataset: Vec<(String, &str, Quartiles)>, backend: DB) -> UnitResult
-where
-    DB::ErrorType: 'static,
-{
-    let lang_list: Vec<_> = dataset
+/// Saves the throughput-vs-cores chart and the parallel efficiency table next to the main
+/// output file, e.g. `result-scaling.svg` and `result-scaling.adoc` (SVG is used if the main
+/// output is not an image).
+fn draw_scaling(file: &str, host: &HostInfo, theme: Theme, points: &[ScalingPoint]) -> UnitResult {
+    let table = scaling::efficiency_table(points, host);
+    log(&table);
+    let table_file = sibling(file, "scaling.adoc");
+    log(&format!("Saving {}", table_file.display()));
+    fs::write(&table_file, table)?;
+    let ext = image_ext(file);
+    let chart_file = sibling(file, &format!("scaling.{ext}"));
+    log(&format!("Saving {}", chart_file.display()));
+    match ext {
+        "svg" => scaling::draw_scaling(
+            points,
+            host,
+            theme,
+            SVGBackend::new(&chart_file, (960, 480)),
+        ),
+        _ => scaling::draw_scaling(
+            points,
+            host,
+            theme,
+            BitMapBackend::new(&chart_file, (960, 480)),
+        ),
+    }
+}
+
+/// The latency and throughput statistics of every language and scenario.
+fn stats_table(results: &[(&str, Measurement)]) -> Table {
+    let mut table = Table::new(&[
+        "Language",
+        "Scenario",
+        "Requests",
+        "Median, ms",
+        "p90, ms",
+        "p99, ms",
+        "Max, ms",
+        "RPS",
+        "Errors",
+        "Server errors",
+        "CPU, s",
+        "Peak RSS, MB",
+        "Requests per CPU-second",
+        "RPS per MB",
+    ]);
+    for (title, measurement) in results {
+        for run in &measurement.scenarios {
+            let (samples, usage) = (&run.samples, run.usage);
+            let latencies = stats::sorted(stats::latencies(samples).into_iter());
+            let percentile = |pct| format!("{:.3}", stats::percentile(&latencies, pct));
+            let rps = stats::rps(samples);
+            let resource = |value: Option<f64>, precision: usize| {
+                value.map_or_else(|| String::from("-"), |x| format!("{x:.precision$}"))
+            };
+            table.push(vec![
+                title.to_string(),
+                scenarios::describe(&run.scenario).1.to_string(),
+                samples.len().to_string(),
+                percentile(50.0),
+                percentile(90.0),
+                percentile(99.0),
+                percentile(100.0),
+                format!("{rps:.0}"),
+                stats::errors(samples, REQUESTS, CONCURRENCY).to_string(),
+                measurement.server_errors.to_string(),
+                resource(usage.map(|x| x.cpu_seconds), 2),
+                resource(usage.map(|x| x.peak_rss_mb), 1),
+                resource(
+                    usage.and_then(|x| x.requests_per_cpu_second(samples.len())),
+                    0,
+                ),
+                resource(usage.and_then(|x| x.rps_per_mb(rps)), 1),
+            ]);
+        }
+    }
+    table
+}
+
+/// The short summary of every language and scenario for the documentation.
+fn summary_table(results: &[(&str, Measurement)]) -> Table {
+    let mut table = Table::new(&[
+        "Language",
+        "Scenario",
+        "Median, ms",
+        "p99, ms",
+        "RPS",
+        "Errors",
+    ]);
+    for (title, measurement) in results {
+        for run in &measurement.scenarios {
+            let latencies = stats::sorted(stats::latencies(&run.samples).into_iter());
+            table.push(vec![
+                title.to_string(),
+                scenarios::describe(&run.scenario).1.to_string(),
+                format!("{:.3}", stats::percentile(&latencies, 50.0)),
+                format!("{:.3}", stats::percentile(&latencies, 99.0)),
+                format!("{:.0}", stats::rps(&run.samples)),
+                stats::errors(&run.samples, REQUESTS, CONCURRENCY).to_string(),
+            ]);
+        }
+    }
+    table
+}
+
+/// Renders the summary table in AsciiDoc or Markdown, followed by the failed languages.
+fn summary(format: &str, results: &Results) -> String {
+    let table = summary_table(&results.measured);
+    let mut lines = vec![match format {
+        "md" => table.markdown(),
+        _ => table.adoc(),
+    }];
+    if !results.failures.is_empty() {
+        lines.push(String::new());
+        lines.extend(
+            results
+                .failures
+                .iter()
+                .map(|x| format!("* {}: failed ({})", x.title, x.phase)),
+        );
+    }
+    let notes: Vec<String> = results
+        .measured
         .iter()
-        .unique_by(|x| x.0.clone())
-        .sorted_by(|a, b| b.2.median().partial_cmp(&a.2.median()).unwrap())
-        .map(|x| x.0.clone())
+        .flat_map(|(title, measurement)| {
+            measurement
+                .notes
+                .iter()
+                .map(move |note| format!("* {title}: {note}"))
+        })
         .collect();
+    if !notes.is_empty() {
+        lines.push(String::new());
+        lines.extend(notes);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Rewrites the sections of the README between the markers, e.g. `// results:begin` and
+/// `// results:end`.
+fn update_readme(path: &str, sections: &[(&str, String)]) -> UnitResult {
+    let mut readme = fs::read_to_string(path)?;
+    for (name, content) in sections {
+        readme = tables::replace_between_markers(&readme, name, content)
+            .ok_or_else(|| format!("The {name} markers are not found in {path}"))?;
+    }
+    fs::write(path, readme)?;
+    log(&format!("Updated {path}"));
+    Ok(())
+}
+
+/// Renders the response time of every request as the tab-separated values, preceded by the
+/// environment and the failure notes as the comments.
+fn tsv(host: &HostInfo, results: &Results) -> String {
+    let notes = results.failures.iter().map(|x| {
+        let error = x.error.replace('\n', "\n#   ");
+        format!("# {}: failed ({}): {error}", x.title, x.phase)
+    });
+    let server_errors = results
+        .measured
+        .iter()
+        .filter(|(_, measurement)| measurement.server_errors > 0)
+        .map(|(title, measurement)| {
+            format!(
+                "# {title}: {} error lines in the server output",
+                measurement.server_errors
+            )
+        });
+    let measurement_notes = results.measured.iter().flat_map(|(title, measurement)| {
+        measurement
+            .notes
+            .iter()
+            .map(move |note| format!("# {title}: {note}"))
+    });
+    let rows = results.measured.iter().flat_map(|(title, measurement)| {
+        measurement.scenarios.iter().flat_map(move |run| {
+            let scenario = scenarios::describe(&run.scenario).1;
+            run.samples
+                .iter()
+                .map(move |x| format!("{title}\t{scenario}\t{}", x.latency))
+        })
+    });
+    std::iter::once(host.comments())
+        .chain(notes)
+        .chain(server_errors)
+        .chain(measurement_notes)
+        .chain(rows)
+        .join("\n")
+}
+
+/// Renders the environment and all the results (including the samples) as JSON.
+fn json(host: &HostInfo, results: &Results) -> Result<String, Box<dyn Error>> {
+    let languages: Vec<serde_json::Value> = results
+        .measured
+        .iter()
+        .map(|(title, measurement)| {
+            let mut value = serde_json::to_value(measurement)?;
+            value["title"] = serde_json::Value::from(*title);
+            Ok(value)
+        })
+        .collect::<Result<_, serde_json::Error>>()?;
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "host": host,
+        "languages": languages,
+        "failures": results.failures,
+        "scaling": results.scaling,
+        "preflight": results.preflight,
+    }))?)
+}
 
-    let mut colors = (0..).map(Palette99::pick);
-    let mut offsets = (-10..).step_by(20);
-    let mut series = BTreeMap::new();
-    for x in dataset.iter() {
-        let entry = series
-            .entry(x.1)
-            .or_insert_with(|| (Vec::new(), colors.next().unwrap(), offsets.next().unwrap()));
-        entry.0.push((x.0.clone(), &x.2));
-    }
-
-    let values: Vec<f32> = dataset.iter().flat_map(|x| x.2.values().to_vec()).collect();
-    let values_range = fitting_range(values.iter());
-
-    let root = backend.into_drawing_area();
-    root.fill(&WHITE)?;
-
-    let mut chart = ChartBuilder::on(&root)
-        .x_label_area_size(40)
-        .y_label_area_size(100)
-        .build_cartesian_2d(0.0..values_range.end + 1.0, lang_list[..].into_segmented())?;
-
-    chart
-        .configure_mesh()
-        .x_desc("Response, ms")
-        .y_desc("Language")
-        .y_labels(lang_list.len())
-        .light_line_style(WHITE)
-        .draw()?;
-
-    for (label, (values, style, offset)) in &series {
-        chart
-            .draw_series(values.iter().map(|x| {
-                Boxplot::new_horizontal(SegmentValue::CenterOf(&x.0), x.1)
-                    .width(10)
-                    .whisker_width(0.5)
-                    .style(style)
-                    .offset(*offset)
-            }))?
-            .label(*label)
-            .legend(move |(x, y)| Rectangle::new([(x, y - 7), (x + 12, y + 5)], style.filled()));
-    }
-    chart
-        .configure_series_labels()
-        .position(SeriesLabelPosition::UpperRight)
-        .background_style(WHITE.filled())
-        .border_style(BLACK.mix(0.5))
-        .legend_area_size(22)
-        .draw()?;
+/// The extensions of the supported output files.
+const OUTPUT_TYPES: [&str; 7] = ["png", "svg", "tsv", "json", "html", "adoc", "md"];
+
+/// Saves the output in the format by the file extension.
+fn save_output(
+    file: &str,
+    host: &HostInfo,
+    opts: &Options,
+    chart_opts: &ChartOptions,
+    results: &Results,
+) -> UnitResult {
+    let ext = Path::new(file)
+        .extension()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    log(&format!("Saving {file}"));
+    match ext {
+        "tsv" => fs::write(file, tsv(host, results))?,
+        "json" => fs::write(file, json(host, results)?)?,
+        format @ ("adoc" | "md") => fs::write(file, summary(format, results))?,
+        "html" => save_report(file, host, opts, chart_opts, results)?,
+        "svg" => chart::draw(
+            &chart_series(results),
+            &chart_gaps(&results.failures),
+            host,
+            chart_opts,
+            SVGBackend::new(file, chart_opts.size.pixels()),
+        )?,
+        "png" => chart::draw(
+            &chart_series(results),
+            &chart_gaps(&results.failures),
+            host,
+            chart_opts,
+            BitMapBackend::new(file, chart_opts.size.pixels()),
+        )?,
+        _ => {
+            return Err(Box::new(SuiteError::UnknownFileType {
+                file: file.to_string(),
+            }))
+        }
+    }
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn load_parameters(opts: &Options) -> Table {
+    let describe = |cpus: &Option<CpuSet>| {
+        cpus.as_ref()
+            .map_or_else(|| String::from("any"), |x| x.to_string())
+    };
+    let mut table = Table::new(&["Parameter", "Value"]);
+    for (name, value) in [
+        ("Requests", REQUESTS.to_string()),
+        ("Concurrency", CONCURRENCY.to_string()),
+        ("Request timeout", format!("{REQUEST_TIMEOUT}s")),
+        ("Warmup batch", format!("{WARMUP_REQUESTS} requests")),
+        (
+            "Warmup tolerance",
+            format!("{}%", opts.warmup.tolerance * 100.0),
+        ),
+        (
+            "Warmup batches",
+            format!("up to {}", opts.warmup.max_batches),
+        ),
+        ("Server CPUs", describe(&opts.server_cpus)),
+        ("Load generator CPUs", describe(&opts.client_cpus)),
+    ] {
+        table.push(vec![name.to_string(), value]);
+    }
+    table
+}
+
+/// Saves the HTML report with the response time and throughput charts, the statistics, the
+/// timelines, the scaling (if any), the failures, the load parameters and the environment.
+fn save_report(
+    file: &str,
+    host: &HostInfo,
+    opts: &Options,
+    chart_opts: &ChartOptions,
+    results: &Results,
+) -> UnitResult {
+    let mut report = Report::new("Simple Web Benchmark");
+    let series = chart_series(results);
+    let gaps = chart_gaps(&results.failures);
+    let mut svg = String::new();
+    chart::draw(
+        &series,
+        &gaps,
+        host,
+        chart_opts,
+        SVGBackend::with_string(&mut svg, chart_opts.size.pixels()),
+    )?;
+    report.chart("Response time", svg);
+    let mut svg = String::new();
+    chart::throughput(
+        &series,
+        &gaps,
+        host,
+        chart_opts,
+        SVGBackend::with_string(&mut svg, chart::throughput_size(&series, chart_opts)),
+    )?;
+    report.chart("Throughput", svg);
+    report.table("Statistics", &stats_table(&results.measured));
+    if !results.failures.is_empty() {
+        let notes: Vec<(String, String)> = results
+            .failures
+            .iter()
+            .map(|x| (format!("{} ({})", x.title, x.phase), x.error.clone()))
+            .collect();
+        report.failures("Failures", &notes);
+    }
+    for (title, measurement) in &results.measured {
+        let scenarios = timeline_series(measurement);
+        let mut svg = String::new();
+        timeline::draw_timeline(
+            title,
+            &scenarios,
+            host,
+            chart_opts.theme,
+            SVGBackend::with_string(&mut svg, (800, 600)),
+        )?;
+        report.chart(&format!("{title} timeline"), svg);
+    }
+    if !results.scaling.is_empty() {
+        let mut svg = String::new();
+        scaling::draw_scaling(
+            &results.scaling,
+            host,
+            chart_opts.theme,
+            SVGBackend::with_string(&mut svg, (960, 480)),
+        )?;
+        report.chart("Scaling", svg);
+    }
+    report.table("Load parameters", &load_parameters(opts));
+    let mut environment = Table::new(&["Property", "Value"]);
+    for (name, value) in host.properties() {
+        environment.push(vec![name.to_string(), value]);
+    }
+    report.table("Environment", &environment);
+    if let Some(snapshot) = &results.preflight {
+        let mut checks = Table::new(&["Property", "Value"]);
+        for (name, value) in snapshot.properties() {
+            checks.push(vec![name.to_string(), value]);
+        }
+        report.table("Pre-flight checks", &checks);
+    }
+
+    log("Fetching versions...");
+    let (langs, frameworks) = probes::run_versions();
+    let (langs, frameworks) = probes::tables(&langs, &frameworks, true);
+    report.table("Language versions", &langs.plain());
+    report.table("Framework versions", &frameworks.plain());
+
+    fs::write(file, report.html())?;
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run_suite() {
+        dashboard::stop();
+        logging::error(&e.to_string());
+        std::process::exit(1);
+    }
+}
+
+fn run_suite() -> Result<(), Box<dyn Error>> {
     let mut lang_cmds = BTreeMap::new();
     lang_cmds.insert(
         "go",
         Cmd {
             title: "Go",
+            requires: &["go"],
+            tags: &["compiled"],
+            sources: &["go/main.go"],
+            artifact: Some("go/build/main"),
             build: Box::new(|| {
                 pexec(Command::new("go").args(["build", "-o", "go/build/main", "go/main.go"]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("go/build/main"))),
+            run: Box::new(|opts| {
+                pspawn(
+                    Command::new("go/build/main")
+                        .envs(opts.workers.map(|n| ("GOMAXPROCS", n.to_string()))),
+                    opts,
+                )
+            }),
         },
     );
     lang_cmds.insert(
         "rust_rocket",
         Cmd {
             title: "Rust/Rocket",
+            requires: &["cargo"],
+            tags: &["rust", "compiled"],
+            sources: &[
+                "rust/rocket/Cargo.toml",
+                "rust/rocket/Cargo.lock",
+                "rust/rocket/src",
+            ],
+            artifact: Some("rust/rocket/target/release/rocket-test"),
             build: Box::new(|| {
                 pexec(Command::new("cargo").args([
                     "build",
@@ -267,13 +1055,26 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "--release",
                 ]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("rust/rocket/target/release/rocket-test"))),
+            run: Box::new(|opts| {
+                pspawn(
+                    &mut Command::new("rust/rocket/target/release/rocket-test"),
+                    opts,
+                )
+            }),
         },
     );
     lang_cmds.insert(
         "rust_warp",
         Cmd {
             title: "Rust/warp",
+            requires: &["cargo"],
+            tags: &["rust", "compiled"],
+            sources: &[
+                "rust/warp/Cargo.toml",
+                "rust/warp/Cargo.lock",
+                "rust/warp/src",
+            ],
+            artifact: Some("rust/warp/target/release/warp-test"),
             build: Box::new(|| {
                 pexec(Command::new("cargo").args([
                     "build",
@@ -282,13 +1083,29 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "--release",
                 ]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("rust/warp/target/release/warp-test"))),
+            run: Box::new(|opts| {
+                pspawn(
+                    Command::new("rust/warp/target/release/warp-test").envs(
+                        opts.workers
+                            .map(|n| ("TOKIO_WORKER_THREADS", n.to_string())),
+                    ),
+                    opts,
+                )
+            }),
         },
     );
     lang_cmds.insert(
         "rust_actix",
         Cmd {
             title: "Rust/Actix",
+            requires: &["cargo"],
+            tags: &["rust", "compiled", "thread-per-core"],
+            sources: &[
+                "rust/actix-web/Cargo.toml",
+                "rust/actix-web/Cargo.lock",
+                "rust/actix-web/src",
+            ],
+            artifact: Some("rust/actix-web/target/release/actix-web-test"),
             build: Box::new(|| {
                 pexec(Command::new("cargo").args([
                     "build",
@@ -297,10 +1114,11 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "--release",
                 ]))
             }),
-            run: Box::new(|| {
-                pspawn(&mut Command::new(
-                    "rust/actix-web/target/release/actix-web-test",
-                ))
+            run: Box::new(|opts| {
+                pspawn(
+                    &mut Command::new("rust/actix-web/target/release/actix-web-test"),
+                    opts,
+                )
             }),
         },
     );
@@ -308,6 +1126,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         "rust_hyper_tokio",
         Cmd {
             title: "Rust/hyper-tokio",
+            requires: &["cargo"],
+            tags: &["rust", "compiled"],
+            sources: &[
+                "rust/hyper-tokio/Cargo.toml",
+                "rust/hyper-tokio/Cargo.lock",
+                "rust/hyper-tokio/src",
+            ],
+            artifact: Some("rust/hyper-tokio/target/release/hyper-tokio-test"),
             build: Box::new(|| {
                 pexec(Command::new("cargo").args([
                     "build",
@@ -316,10 +1142,14 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "--release",
                 ]))
             }),
-            run: Box::new(|| {
-                pspawn(&mut Command::new(
-                    "rust/hyper-tokio/target/release/hyper-tokio-test",
-                ))
+            run: Box::new(|opts| {
+                pspawn(
+                    Command::new("rust/hyper-tokio/target/release/hyper-tokio-test").envs(
+                        opts.workers
+                            .map(|n| ("TOKIO_WORKER_THREADS", n.to_string())),
+                    ),
+                    opts,
+                )
             }),
         },
     );
@@ -327,6 +1157,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         "rust_hyper_monoio",
         Cmd {
             title: "Rust/hyper-monoio",
+            requires: &["cargo"],
+            tags: &["rust", "compiled", "thread-per-core"],
+            sources: &[
+                "rust/hyper-monoio/Cargo.toml",
+                "rust/hyper-monoio/Cargo.lock",
+                "rust/hyper-monoio/src",
+            ],
+            artifact: Some("rust/hyper-monoio/target/release/hyper-monoio-test"),
             build: Box::new(|| {
                 pexec(Command::new("cargo").args([
                     "build",
@@ -335,10 +1173,11 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "--release",
                 ]))
             }),
-            run: Box::new(|| {
-                pspawn(&mut Command::new(
-                    "rust/hyper-monoio/target/release/hyper-monoio-test",
-                ))
+            run: Box::new(|opts| {
+                pspawn(
+                    &mut Command::new("rust/hyper-monoio/target/release/hyper-monoio-test"),
+                    opts,
+                )
             }),
         },
     );
@@ -346,14 +1185,29 @@ fn main() -> Result<(), Box<dyn Error>> {
         "nodejs",
         Cmd {
             title: "Node.js",
+            requires: &["node"],
+            tags: &["jit"],
+            sources: &[],
+            artifact: None,
             build: Box::new(|| Ok(())),
-            run: Box::new(|| pspawn(Command::new("node").args(["nodejs/main.js"]))),
+            run: Box::new(|opts| {
+                pspawn(
+                    Command::new("node")
+                        .args(["nodejs/main.js"])
+                        .args(opts.workers.map(|n| format!("--workers={n}"))),
+                    opts,
+                )
+            }),
         },
     );
     lang_cmds.insert(
         "crystal",
         Cmd {
             title: "Crystal",
+            requires: &["crystal"],
+            tags: &["compiled"],
+            sources: &["crystal/server.cr"],
+            artifact: Some("crystal/server"),
             build: Box::new(|| {
                 pexec(Command::new("crystal").args([
                     "build",
@@ -364,48 +1218,65 @@ fn main() -> Result<(), Box<dyn Error>> {
                     "crystal/server.cr",
                 ]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("crystal/server"))),
+            run: Box::new(|opts| pspawn(&mut Command::new("crystal/server"), opts)),
         },
     );
     lang_cmds.insert(
         "d_serverino",
         Cmd {
             title: "D/Serverino",
+            requires: &["dub", "ldc2"],
+            tags: &["d", "compiled"],
+            sources: &["d/serverino/dub.json", "d/serverino/source"],
+            artifact: Some("d/serverino/d_serverino"),
             build: Box::new(|| {
                 pexec(Command::new("dub").args(["build", "--root=d/serverino", "-b=release"]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("d/serverino/d_serverino"))),
+            run: Box::new(|opts| pspawn(&mut Command::new("d/serverino/d_serverino"), opts)),
         },
     );
     lang_cmds.insert(
         "d_vibed",
         Cmd {
             title: "D/Vibe.D",
+            requires: &["dub", "ldc2"],
+            tags: &["d", "compiled"],
+            sources: &["d/vibed/dub.json", "d/vibed/source"],
+            artifact: Some("d/vibed/d_vibed"),
             build: Box::new(|| {
                 pexec(Command::new("dub").args(["build", "--root=d/vibed", "-b=release"]))
             }),
-            run: Box::new(|| pspawn(&mut Command::new("d/vibed/d_vibed"))),
+            run: Box::new(|opts| pspawn(&mut Command::new("d/vibed/d_vibed"), opts)),
         },
     );
     lang_cmds.insert(
         "python",
         Cmd {
             title: "PyPy3/Twisted",
+            requires: &["pypy3", "twisted"],
+            tags: &["jit"],
+            sources: &[],
+            artifact: None,
             build: Box::new(|| Ok(())),
-            run: Box::new(|| pspawn(Command::new("pypy3").args(["python/twist.py"]))),
+            run: Box::new(|opts| pspawn(Command::new("pypy3").args(["python/twist.py"]), opts)),
         },
     );
     lang_cmds.insert(
         "php_swoole",
         Cmd {
             title: "PHP/Swoole",
+            requires: &["php", "openswoole"],
+            tags: &["interpreted"],
+            sources: &[],
+            artifact: None,
             build: Box::new(|| Ok(())),
-            run: Box::new(|| {
-                pspawn(Command::new("php").args([
-                    "-c",
-                    "php/swoole/php.ini",
-                    "php/swoole/main.php",
-                ]))
+            run: Box::new(|opts| {
+                pspawn(
+                    Command::new("php")
+                        .args(["-c", "php/swoole/php.ini", "php/swoole/main.php"])
+                        .args(opts.workers.map(|n| format!("--workers={n}"))),
+                    opts,
+                )
             }),
         },
     );
@@ -419,89 +1290,569 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .short('o')
                 .long("out")
                 .value_name("file")
-                .help("Sets an image file to generate (PNG/SVG/TSV are supported)")
+                .help("Sets an output file to generate, could be repeated (PNG/SVG/TSV/JSON/HTML/AsciiDoc/Markdown are supported)")
                 .default_value(default_file)
+                .default_missing_value(default_file)
+                .action(clap::ArgAction::Append)
                 .num_args(0..=1),
         )
+        .arg(
+            Arg::new("scenario")
+                .long("scenario")
+                .value_name("name")
+                .help("Sets a scenario to run, could be repeated (all by default)")
+                .value_parser(scenarios::names())
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("warmup-tolerance")
+                .long("warmup-tolerance")
+                .value_name("percent")
+                .help("Sets the allowed median latency change between warmup batches")
+                .value_parser(value_parser!(f64))
+                .default_value("5"),
+        )
+        .arg(
+            Arg::new("warmup-max")
+                .long("warmup-max")
+                .value_name("batches")
+                .help("Sets the maximum number of warmup batches")
+                .value_parser(value_parser!(u32).range(2..))
+                .default_value("10"),
+        )
+        .arg(
+            Arg::new("chart")
+                .long("chart")
+                .value_name("type")
+                .help("Sets the response time chart type")
+                .value_parser(value_parser!(ChartType))
+                .default_value("boxplot"),
+        )
+        .arg(
+            Arg::new("chart-size")
+                .long("chart-size")
+                .value_name("WxH")
+                .help("Sets the chart dimensions in pixels")
+                .value_parser(value_parser!(Size))
+                .default_value("480x640"),
+        )
+        .arg(
+            Arg::new("log-scale")
+                .long("log-scale")
+                .action(clap::ArgAction::SetTrue)
+                .help("Uses the logarithmic response time axis"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("key")
+                .help("Sets the order of the languages on the chart (the best at the top)")
+                .value_parser(value_parser!(SortKey))
+                .default_value("median"),
+        )
+        .arg(
+            Arg::new("title")
+                .long("title")
+                .value_name("text")
+                .help("Sets the chart title"),
+        )
+        .arg(
+            Arg::new("subtitle")
+                .long("subtitle")
+                .value_name("text")
+                .help("Sets the chart subtitle"),
+        )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .value_name("theme")
+                .help("Sets the chart colors")
+                .value_parser(value_parser!(Theme))
+                .default_value("light"),
+        )
+        .arg(
+            Arg::new("legend")
+                .long("legend")
+                .value_name("position")
+                .help("Sets the chart legend position")
+                .value_parser(value_parser!(Legend))
+                .default_value("upper-right"),
+        )
+        .arg(
+            Arg::new("update-readme")
+                .long("update-readme")
+                .value_name("file")
+                .help("Rewrites the results summary and the environment in the README between the markers")
+                .num_args(0..=1)
+                .default_missing_value("README.adoc"),
+        )
+        .arg(
+            Arg::new("timeline")
+                .long("timeline")
+                .action(clap::ArgAction::SetTrue)
+                .help("Generates the latency-over-time chart for every language"),
+        )
+        .arg(
+            Arg::new("throughput")
+                .long("throughput")
+                .action(clap::ArgAction::SetTrue)
+                .help("Generates the throughput and efficiency chart"),
+        )
+        .arg(
+            Arg::new("server-cpus")
+                .long("server-cpus")
+                .value_name("list")
+                .help("Pins the server to the CPUs (e.g. 0-3,6)")
+                .value_parser(value_parser!(CpuSet)),
+        )
+        .arg(
+            Arg::new("client-cpus")
+                .long("client-cpus")
+                .value_name("list")
+                .help("Pins the load generator to the CPUs (e.g. 4-7)")
+                .value_parser(value_parser!(CpuSet)),
+        )
+        .arg(
+            Arg::new("scaling")
+                .long("scaling")
+                .action(clap::ArgAction::SetTrue)
+                .help("Reruns every language restricted to 1, 2, 4, ... server cores"),
+        )
+        .arg(
+            Arg::new("results")
+                .long("results")
+                .value_name("dir")
+                .help("Sets the directory for the run results (the server output, etc.)")
+                .default_value("results"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .value_name("run-dir")
+                .help("Continues the run, skipping the languages already completed in it")
+                .conflicts_with("results"),
+        )
+        .arg(
+            Arg::new("no-build")
+                .long("no-build")
+                .action(clap::ArgAction::SetTrue)
+                .help("Skips the build, using the previously built servers"),
+        )
+        .arg(
+            Arg::new("build-only")
+                .long("build-only")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("no-build")
+                .help("Builds the servers without running the benchmarks"),
+        )
+        .arg(
+            Arg::new("force-build")
+                .long("force-build")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("no-build")
+                .help("Rebuilds the servers even if they are up to date"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(clap::ArgAction::SetTrue)
+                .help("Refuses to run if the pre-flight checks find any problems"),
+        )
+        .arg(
+            Arg::new("tui")
+                .long("tui")
+                .action(clap::ArgAction::SetTrue)
+                .help("Shows the live dashboard (if the output is a terminal)"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["verbose", "trace"])
+                .help("Shows only the warnings and the errors"),
+        )
         .arg(
             Arg::new("verbose")
                 .long("verbose")
                 .action(clap::ArgAction::SetTrue)
                 .help("Enables the verbose output"),
         )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .action(clap::ArgAction::SetTrue)
+                .help("Enables the verbose output with every executed command"),
+        )
+        .arg(
+            Arg::new("log-file")
+                .long("log-file")
+                .value_name("file")
+                .help("Saves the log with all the details regardless of the verbosity"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("target")
+                .action(clap::ArgAction::Append)
+                .help("Skips the languages (the same as the selection, could be repeated)"),
+        )
         .arg(
             Arg::new("lang")
                 .index(1)
                 .action(clap::ArgAction::Append)
                 .required(true)
-                .help("Sets the languages to test ('all' for all)"),
+                .help("Sets the languages to test: the names, globs (e.g. 'rust_*'), tags or 'all'"),
+        )
+        .subcommand(
+            clap::Command::new("doctor")
+                .about("Checks the tools required by the languages")
+                .arg(
+                    Arg::new("exclude")
+                        .long("exclude")
+                        .value_name("target")
+                        .action(clap::ArgAction::Append)
+                        .help("Skips the languages (the same as the selection, could be repeated)"),
+                )
+                .arg(
+                    Arg::new("lang")
+                        .index(1)
+                        .action(clap::ArgAction::Append)
+                        .default_value("all")
+                        .help("Sets the languages to check: the names, globs (e.g. 'rust_*'), tags or 'all'"),
+                ),
         )
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
         .after_help(format!(
-            "The following languages are supported: {}.",
-            lang_cmds.keys().join(", ")
+            "The following languages are supported: {}.\n\nThe following tags are supported:\n{}",
+            lang_cmds.keys().join(", "),
+            targets::TAGS
+                .iter()
+                .map(|(tag, description)| format!("  {tag:<17}{description}"))
+                .join("\n")
         ))
         .get_matches();
-    let verbose = matches.contains_id("verbose");
-    let mut langs: Vec<String> = matches
-        .get_many::<String>("lang")
-        .unwrap()
-        .map(|s| s.to_string())
-        .collect();
-    if langs.iter().any(|x| x == "all") {
-        langs = lang_cmds.keys().map(|key| key.to_string()).collect();
+
+    let selected_langs = |matches: &clap::ArgMatches| -> Selection {
+        let values = |id| {
+            matches
+                .get_many::<String>(id)
+                .map_or_else(Vec::new, |x| x.cloned().collect())
+        };
+        targets::select(&lang_cmds, &values("lang"), &values("exclude")).unwrap_or_else(|e| {
+            logging::error(&e.to_string());
+            std::process::exit(1);
+        })
+    };
+    if let Some(doctor_matches) = matches.subcommand_matches("doctor") {
+        let langs = selected_langs(doctor_matches).langs;
+        let availability = doctor::check(&lang_cmds, &langs, &doctor::RUNNER_REQUIREMENTS);
+        if doctor::summary(&lang_cmds, &availability) > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
-    let file = matches.get_one::<String>("out").unwrap();
-    let ext = Path::new(file)
-        .extension()
-        .ok_or_else(|| Box::new(errors::UnknownFileTypeError {}))?;
-    let save_for_print = ext.to_str().map_or(false, |x| x == "tsv");
 
-    let mut dataset = Vec::new();
-    let mut dataset_for_print = Vec::new();
+    let level = if matches.get_flag("trace") {
+        Level::Trace
+    } else if matches.get_flag("verbose") {
+        Level::Verbose
+    } else if matches.get_flag("quiet") {
+        Level::Quiet
+    } else {
+        Level::Normal
+    };
+    logging::init(level, matches.get_one::<String>("log-file").map(Path::new))?;
+    let selection = selected_langs(&matches);
+    let build_only = matches.get_flag("build-only");
+    // Only the build tools are needed to build the servers
+    let common: &[&str] = if build_only {
+        &[]
+    } else {
+        &doctor::RUNNER_REQUIREMENTS
+    };
+    let availability = doctor::check(&lang_cmds, &selection.langs, common);
+    doctor::summary(&lang_cmds, &availability);
+    let mut langs = selection.langs.clone();
+    langs.retain(|lang| {
+        selection.is_named(lang) || availability.get(lang).is_none_or(|x| x.is_empty())
+    });
+    // E.g. the load generator is missing, so none of the languages is runnable
+    if langs.is_empty() {
+        logging::error(&SuiteError::NoLanguages.to_string());
+        std::process::exit(1);
+    }
+    if langs.len() < selection.langs.len() {
+        log(&format!(
+            "Skipping the unavailable languages, running: {}",
+            langs.join(", ")
+        ));
+    }
+    let no_build = matches.get_flag("no-build");
+    let force_build = matches.get_flag("force-build");
+    if build_only {
+        let failures = prebuild::build_all(&lang_cmds, &langs, force_build);
+        for (lang, error) in &failures {
+            logging::error(&format!("  {lang}: {error}"));
+        }
+        if !failures.is_empty() {
+            std::process::exit(if failures.len() == langs.len() { 1 } else { 2 });
+        }
+        return Ok(());
+    }
+    let outputs: Vec<&String> = matches.get_many::<String>("out").unwrap().collect();
+    // Checked before the run, not to lose the results of the whole run to a typo
+    if let Some(file) = outputs.iter().find(|x| {
+        Path::new(x)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_none_or(|ext| !OUTPUT_TYPES.contains(&ext))
+    }) {
+        return Err(Box::new(SuiteError::UnknownFileType {
+            file: file.to_string(),
+        }));
+    }
+    let opts = Options {
+        warmup: Warmup {
+            tolerance: matches.get_one::<f64>("warmup-tolerance").unwrap() / 100.0,
+            max_batches: *matches.get_one::<u32>("warmup-max").unwrap(),
+        },
+        server_cpus: matches.get_one::<CpuSet>("server-cpus").cloned(),
+        client_cpus: matches.get_one::<CpuSet>("client-cpus").cloned(),
+        workers: None,
+        server_log: None,
+        scenarios: match matches.get_many::<String>("scenario") {
+            Some(names) => scenarios::SCENARIOS
+                .iter()
+                .filter(|x| names.clone().any(|name| name == x.name))
+                .collect(),
+            None => scenarios::SCENARIOS.iter().collect(),
+        },
+        // Only if the resources are shown, as the sampling competes with the server
+        sample_resources: matches.get_flag("throughput")
+            || matches.get_flag("tui")
+            || outputs
+                .iter()
+                .any(|x| Path::new(x).extension().is_some_and(|ext| ext == "html")),
+    };
+    check_affinity(&opts);
+    let scaling_cpus = if matches.get_flag("scaling") {
+        let cpus = opts
+            .server_cpus
+            .clone()
+            .or_else(affinity::current)
+            .ok_or("Unable to detect the available CPUs")?;
+        log(&format!(
+            "Scaling mode: {} cores",
+            scaling::core_counts(cpus.len()).iter().join(", ")
+        ));
+        Some(cpus)
+    } else {
+        None
+    };
+    // The additional charts and tables are saved next to the first output
+    let file = outputs[0];
+    let host = HostInfo::collect();
+    log(&format!("Host: {}", host.summary().join(", ")));
+    save_environment(file, &host)?;
+    let run_dir = match matches.get_one::<String>("resume") {
+        Some(dir) => {
+            if !Path::new(dir).is_dir() {
+                return Err(format!("Unable to resume, {dir} is not a directory").into());
+            }
+            log(&format!("Resuming {dir}"));
+            PathBuf::from(dir)
+        }
+        None => Path::new(matches.get_one::<String>("results").unwrap())
+            .join(Local::now().format("%Y%m%d-%H%M%S").to_string()),
+    };
+    fs::create_dir_all(&run_dir)?;
+    log(&format!("Saving the results to {}", run_dir.display()));
+    let snapshot = run_preflight(&run_dir, &host, matches.get_flag("strict"))?;
+    let chart_opts = ChartOptions {
+        kind: *matches.get_one::<ChartType>("chart").unwrap(),
+        size: *matches.get_one::<Size>("chart-size").unwrap(),
+        log_scale: matches.get_flag("log-scale"),
+        sort: *matches.get_one::<SortKey>("sort").unwrap(),
+        title: matches.get_one::<String>("title").cloned(),
+        subtitle: matches.get_one::<String>("subtitle").cloned(),
+        theme: *matches.get_one::<Theme>("theme").unwrap(),
+        legend: *matches.get_one::<Legend>("legend").unwrap(),
+    };
+    let timeline = matches.get_flag("timeline");
+    let throughput = matches.get_flag("throughput");
+
+    let mut completed = Vec::new();
+    let mut results = Results {
+        preflight: Some(snapshot),
+        ..Default::default()
+    };
+    let mut saved = BTreeMap::new();
+    for lang in &langs {
+        match Checkpoint::load(&run_dir, lang) {
+            Ok(Some(mut checkpoint)) => {
+                // The results of the scaling mode are required if it's enabled, and the results
+                // of all the selected scenarios
+                let complete = scaling_cpus.is_none() || !checkpoint.scaling.is_empty();
+                if checkpoint.retain(&opts.scenarios) && complete {
+                    saved.insert(lang.clone(), checkpoint);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => logging::warn(&format!("ignoring the saved results of {lang} ({e})")),
+        }
+    }
+    let mut build_failures = if no_build {
+        BTreeMap::new()
+    } else {
+        let unsaved: Vec<String> = langs
+            .iter()
+            .filter(|x| !saved.contains_key(*x))
+            .cloned()
+            .collect();
+        prebuild::build_all(&lang_cmds, &unsaved, force_build)
+    };
+    if matches.get_flag("tui") {
+        dashboard::start(langs.len());
+    }
     for (lang, lang_cmd) in &lang_cmds {
         if !langs.iter().any(|x| x == lang) {
             continue;
         }
 
-        while kill_processes().unwrap() {
-            // kill until all died
-        }
-        let (index_values, pattern_values) = run(lang_cmd, verbose).unwrap();
-        if save_for_print {
-            for x in index_values {
-                dataset_for_print.push(format!(
-                    "{}\t{}\t{}",
-                    String::from(lang_cmd.title),
-                    INDEX,
-                    x
-                ));
+        logging::set_target(Some(lang_cmd.title));
+        let started = Instant::now();
+        let server_log = ServerLog::new(&run_dir, lang);
+        let is_saved = saved.contains_key(*lang);
+        let result = if let Some(checkpoint) = saved.remove(*lang) {
+            log("Using the saved results");
+            results.scaling.extend(checkpoint.scaling);
+            Ok(checkpoint.measurement)
+        } else if let Some(e) = build_failures.remove(*lang) {
+            Err((Phase::Build, e.into()))
+        } else {
+            server_log.reset()?;
+            let lang_opts = Options {
+                server_log: Some(server_log.clone()),
+                ..opts.clone()
+            };
+            let mut points = Vec::new();
+            let result = if let Some(cpus) = &scaling_cpus {
+                run_scaling(lang_cmd, &lang_opts, cpus, &mut points)
+            } else {
+                kill_leftovers();
+                run(lang_cmd, &lang_opts)
+            };
+            result.and_then(|measurement| {
+                let checkpoint = Checkpoint {
+                    measurement,
+                    scaling: points,
+                };
+                checkpoint
+                    .save(&run_dir, lang)
+                    .map_err(|e| (Phase::Load, e))?;
+                results.scaling.extend(checkpoint.scaling);
+                Ok(checkpoint.measurement)
+            })
+        };
+        let measurement = match result {
+            Ok(measurement) => measurement,
+            Err((phase, e)) => {
+                let e = errors::with_target(e, lang_cmd.title);
+                logging::error(&format!("Failed ({phase}): {e}"));
+                let mut error = e.to_string();
+                let tail = match phase {
+                    Phase::Build => String::new(),
+                    _ => server_log.tail(),
+                };
+                if !tail.is_empty() {
+                    logging::error(&format!(
+                        "The server output ({}):\n{tail}",
+                        server_log.path.display()
+                    ));
+                    error.push_str(&format!("\nThe server output:\n{tail}"));
+                }
+                results.failures.push(Failure {
+                    title: String::from(lang_cmd.title),
+                    phase,
+                    error,
+                });
+                logging::set_target(None);
+                dashboard::target_done(None);
+                continue;
             }
-            for x in pattern_values {
-                dataset_for_print.push(format!(
-                    "{}\t{}\t{}",
-                    String::from(lang_cmd.title),
-                    PATTERN,
-                    x
+        };
+        logging::set_target(None);
+        dashboard::target_done((!is_saved).then(|| started.elapsed()));
+        completed.push(lang_cmd.title);
+        let mut measurement = measurement;
+        if timeline {
+            // The measurement is kept even if the chart fails to be saved
+            if let Err(e) = draw_timeline(
+                file,
+                &host,
+                lang,
+                lang_cmd.title,
+                &measurement,
+                chart_opts.theme,
+            ) {
+                logging::error(&format!(
+                    "[{}] Unable to save the timeline: {e}",
+                    lang_cmd.title
                 ));
+                measurement
+                    .notes
+                    .push(format!("the timeline is not saved: {e}"));
             }
-        } else {
-            dataset.push((
-                String::from(lang_cmd.title),
-                "Index URL Request",
-                Quartiles::new(&index_values),
-            ));
-            dataset.push((
-                String::from(lang_cmd.title),
-                "Pattern URL Request",
-                Quartiles::new(&pattern_values),
-            ));
         }
+        results.measured.push((lang_cmd.title, measurement));
+    }
+
+    dashboard::stop();
+
+    if throughput {
+        draw_throughput(file, &host, &chart_opts, &results)?;
+    }
+    if scaling_cpus.is_some() {
+        draw_scaling(file, &host, chart_opts.theme, &results.scaling)?;
+    }
+    // A failed output doesn't prevent saving the rest of them
+    let mut unsaved = 0;
+    for file in outputs {
+        if let Err(e) = save_output(file, &host, &opts, &chart_opts, &results) {
+            logging::error(&format!("Unable to save {file}: {e}"));
+            unsaved += 1;
+        }
+    }
+
+    if let Some(path) = matches.get_one::<String>("update-readme") {
+        update_readme(
+            path,
+            &[
+                ("results", summary("adoc", &results)),
+                ("environment", host.asciidoc()),
+            ],
+        )?;
     }
 
-    match ext.to_str().unwrap() {
-        "tsv" => fs::write(file, dataset_for_print.join("\n"))?,
-        "svg" => draw(dataset, SVGBackend::new(file, (480, 640)))?,
-        _ => draw(dataset, BitMapBackend::new(file, (480, 640)))?,
+    log(&format!(
+        "Completed {} languages: {}",
+        completed.len(),
+        completed.join(", ")
+    ));
+    if unsaved > 0 {
+        std::process::exit(1);
+    }
+    if !results.failures.is_empty() {
+        logging::error(&format!("Failed {} languages:", results.failures.len()));
+        for failure in &results.failures {
+            logging::error(&format!(
+                "  {} ({}): {}",
+                failure.title, failure.phase, failure.error
+            ));
+        }
+        // 1 if nothing has been measured, 2 for the partial failure
+        std::process::exit(if completed.is_empty() { 1 } else { 2 });
     }
     Ok(())
 }
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::SystemTime;
+
+use crate::logging::{self, log};
+use crate::{errors, Cmd};
+
+/// Returns the modification time of the newest file in the paths (the directories are walked
+/// recursively).
+fn newest(paths: &[&str]) -> io::Result<Option<SystemTime>> {
+    fn walk(path: &Path, result: &mut Option<SystemTime>) -> io::Result<()> {
+        let metadata = fs::metadata(path)?;
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path)? {
+                walk(&entry?.path(), result)?;
+            }
+        } else {
+            let modified = metadata.modified()?;
+            *result = Some(result.map_or(modified, |x| x.max(modified)));
+        }
+        Ok(())
+    }
+
+    let mut result = None;
+    for path in paths {
+        walk(Path::new(path), &mut result)?;
+    }
+    Ok(result)
+}
+
+/// Checks if the artifact is newer than all the sources (the languages without the artifact
+/// have nothing to build).
+pub fn is_up_to_date(cmd: &Cmd) -> bool {
+    let Some(artifact) = cmd.artifact else {
+        return true;
+    };
+    let built = match fs::metadata(artifact).and_then(|x| x.modified()) {
+        Ok(built) => built,
+        Err(_) => return false,
+    };
+    match newest(cmd.sources) {
+        Ok(Some(changed)) => changed <= built,
+        Ok(None) => true,
+        Err(e) => {
+            logging::warn(&format!(
+                "unable to check the sources of {} ({e})",
+                cmd.title
+            ));
+            false
+        }
+    }
+}
+
+/// Builds the languages concurrently (skipping the up-to-date ones unless forced), and returns
+/// the build errors.
+pub fn build_all(
+    lang_cmds: &BTreeMap<&str, Cmd>,
+    langs: &[String],
+    force: bool,
+) -> BTreeMap<String, String> {
+    let selected: Vec<(&str, &Cmd)> = lang_cmds
+        .iter()
+        .filter(|(lang, _)| langs.iter().any(|x| x == *lang))
+        .filter(|(_, cmd)| {
+            let skip = !force && is_up_to_date(cmd);
+            if skip && cmd.artifact.is_some() {
+                log(&format!("{} is up to date", cmd.title));
+            }
+            !skip
+        })
+        .map(|(lang, cmd)| (*lang, cmd))
+        .collect();
+    if selected.is_empty() {
+        return BTreeMap::new();
+    }
+
+    log(&format!(
+        "Building {}...",
+        selected
+            .iter()
+            .map(|(_, cmd)| cmd.title)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    logging::set_phase(Some("build"));
+    // The errors aren't Send, so they are passed as the text
+    let results: Vec<(&str, Result<(), String>)> = thread::scope(|scope| {
+        let handles: Vec<_> = selected
+            .iter()
+            .map(|(lang, cmd)| {
+                let build = &cmd.build;
+                let title = cmd.title;
+                (
+                    *lang,
+                    scope.spawn(move || {
+                        build().map_err(|e| errors::with_target(e, title).to_string())
+                    }),
+                )
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(lang, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err(String::from("the build has panicked")));
+                (lang, result)
+            })
+            .collect()
+    });
+    logging::set_phase(None);
+
+    let mut failures = BTreeMap::new();
+    for (lang, result) in results {
+        let title = lang_cmds[lang].title;
+        match result {
+            Ok(()) => log(&format!("{title} is built")),
+            Err(e) => {
+                logging::error(&format!("{title} build failed"));
+                failures.insert(lang.to_string(), e);
+            }
+        }
+    }
+    failures
+}
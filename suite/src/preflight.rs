@@ -0,0 +1,185 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use crate::host::{meminfo, read, HostInfo};
+use crate::resources;
+
+/// Processes using more than this share of a single CPU are reported as competing.
+const BUSY_CPU_PERCENT: f64 = 10.0;
+/// Load average per CPU above which the host is considered noisy.
+const MAX_LOAD_PER_CPU: f64 = 0.1;
+const MIN_AVAILABLE_MB: u64 = 1024;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize)]
+pub struct BusyProcess {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f64,
+}
+
+/// The state of the host right before the benchmarks.
+#[derive(Serialize)]
+pub struct Snapshot {
+    pub host: HostInfo,
+    pub cpus: usize,
+    /// The CPU frequency governors (mapped to the number of CPUs using them).
+    pub governors: BTreeMap<String, usize>,
+    pub turbo: Option<bool>,
+    pub load_average: Option<[f64; 3]>,
+    pub mem_total_mb: Option<u64>,
+    pub mem_available_mb: Option<u64>,
+    pub busy_processes: Vec<BusyProcess>,
+    pub warnings: Vec<String>,
+}
+
+fn governors() -> BTreeMap<String, usize> {
+    let mut result = BTreeMap::new();
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else {
+        return result;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path().join("cpufreq/scaling_governor");
+        if let Ok(governor) = fs::read_to_string(path) {
+            *result.entry(governor.trim().to_string()).or_insert(0) += 1;
+        }
+    }
+    result
+}
+
+fn turbo() -> Option<bool> {
+    if let Some(no_turbo) = read("/sys/devices/system/cpu/intel_pstate/no_turbo") {
+        return Some(no_turbo == "0");
+    }
+    read("/sys/devices/system/cpu/cpufreq/boost").map(|boost| boost == "1")
+}
+
+fn load_average() -> Option<[f64; 3]> {
+    let content = read("/proc/loadavg")?;
+    let values: Vec<f64> = content
+        .split_whitespace()
+        .take(3)
+        .filter_map(|x| x.parse().ok())
+        .collect();
+    values.try_into().ok()
+}
+
+/// Samples the CPU usage of all processes and returns the ones exceeding the threshold.
+fn busy_processes() -> Vec<BusyProcess> {
+    let ticks = resources::ticks_per_second();
+    let before: BTreeMap<u32, (String, u64)> = resources::pids()
+        .into_iter()
+        .filter_map(|pid| Some((pid, resources::cpu_time(pid)?)))
+        .collect();
+    thread::sleep(SAMPLE_INTERVAL);
+    let own_pid = std::process::id();
+    let mut result: Vec<BusyProcess> = before
+        .into_iter()
+        .filter(|(pid, _)| *pid != own_pid)
+        .filter_map(|(pid, (name, start))| {
+            let (_, end) = resources::cpu_time(pid)?;
+            let cpu_percent =
+                100.0 * end.saturating_sub(start) as f64 / ticks / SAMPLE_INTERVAL.as_secs_f64();
+            (cpu_percent > BUSY_CPU_PERCENT).then_some(BusyProcess {
+                pid,
+                name,
+                cpu_percent,
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap());
+    result
+}
+
+/// Inspects the host for the things known to skew the results.
+pub fn check(host: &HostInfo) -> Snapshot {
+    let cpus = std::thread::available_parallelism().map_or(1, |x| x.get());
+    let mut snapshot = Snapshot {
+        host: host.clone(),
+        cpus,
+        governors: governors(),
+        turbo: turbo(),
+        load_average: load_average(),
+        mem_total_mb: meminfo("MemTotal"),
+        mem_available_mb: meminfo("MemAvailable"),
+        busy_processes: busy_processes(),
+        warnings: Vec::new(),
+    };
+
+    let mut warnings = Vec::new();
+    for (governor, count) in &snapshot.governors {
+        if governor != "performance" {
+            warnings.push(format!(
+                "{count} CPUs use the '{governor}' frequency governor instead of 'performance'"
+            ));
+        }
+    }
+    if snapshot.turbo == Some(true) {
+        warnings.push(String::from(
+            "Turbo boost is enabled, the CPU frequency may vary",
+        ));
+    }
+    if let Some([load, _, _]) = snapshot.load_average {
+        if load > MAX_LOAD_PER_CPU * cpus as f64 {
+            warnings.push(format!("The load average is {load:.2} on {cpus} CPUs"));
+        }
+    }
+    if let Some(available) = snapshot.mem_available_mb {
+        if available < MIN_AVAILABLE_MB {
+            warnings.push(format!("Only {available} MB of memory is available"));
+        }
+    }
+    for process in &snapshot.busy_processes {
+        warnings.push(format!(
+            "Process {} ({}) uses {:.0}% CPU",
+            process.pid, process.name, process.cpu_percent
+        ));
+    }
+    snapshot.warnings = warnings;
+    snapshot
+}
+
+impl Snapshot {
+    /// The checked properties as the name-value pairs for the reports.
+    pub fn properties(&self) -> Vec<(&'static str, String)> {
+        let unknown = || String::from("unknown");
+        let governors = self
+            .governors
+            .iter()
+            .map(|(governor, count)| format!("{governor} ({count} CPUs)"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let busy = self
+            .busy_processes
+            .iter()
+            .map(|x| format!("{} ({}, {:.0}% CPU)", x.name, x.pid, x.cpu_percent))
+            .collect::<Vec<_>>()
+            .join(", ");
+        vec![
+            ("CPUs", self.cpus.to_string()),
+            ("Frequency governors", governors),
+            (
+                "Turbo boost",
+                self.turbo.map_or_else(unknown, |x| {
+                    String::from(if x { "enabled" } else { "disabled" })
+                }),
+            ),
+            (
+                "Load average",
+                self.load_average.map_or_else(unknown, |x| {
+                    format!("{:.2}, {:.2}, {:.2}", x[0], x[1], x[2])
+                }),
+            ),
+            (
+                "Available memory",
+                self.mem_available_mb
+                    .map_or_else(unknown, |x| format!("{x} MB")),
+            ),
+            ("Busy processes", busy),
+            ("Warnings", self.warnings.join("; ")),
+        ]
+    }
+}
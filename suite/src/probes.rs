@@ -0,0 +1,559 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::errors::SuiteError;
+use crate::tables::Table;
+
+pub type StringResult = Result<String, Box<dyn Error>>;
+pub type ProbeFn = Box<dyn Fn() -> StringResult + Send + Sync>;
+
+/// `go run`, `scala version` and JVM startups could take a while, but should not hang the probes.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    static LDC_PATTERN: Regex = Regex::new(r"LDC - the LLVM D compiler \((.*)\)").unwrap();
+    static BOOST_PATTERN: Regex = Regex::new(r#"#define BOOST_LIB_VERSION "(.*)""#).unwrap();
+}
+
+const STANDARD_LIBRARY: &str = "standard library";
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Missing,
+    Timeout,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Status::Ok => "ok",
+            Status::Missing => "missing",
+            Status::Timeout => "timeout",
+            Status::Error => "error",
+        };
+        write!(f, "{text}")
+    }
+}
+
+pub struct Outcome {
+    pub version: Option<String>,
+    pub status: Status,
+    pub detail: Option<String>,
+}
+
+impl Outcome {
+    fn new(result: StringResult) -> Outcome {
+        match result {
+            Ok(version) => Outcome {
+                version: Some(version.trim().to_string()),
+                status: Status::Ok,
+                detail: None,
+            },
+            Err(e) => {
+                let status = match e.downcast_ref::<std::io::Error>() {
+                    Some(io) if io.kind() == std::io::ErrorKind::NotFound => Status::Missing,
+                    _ if matches!(
+                        e.downcast_ref::<SuiteError>(),
+                        Some(SuiteError::Timeout { .. })
+                    ) =>
+                    {
+                        Status::Timeout
+                    }
+                    _ => Status::Error,
+                };
+                Outcome {
+                    version: None,
+                    status,
+                    detail: Some(e.to_string()),
+                }
+            }
+        }
+    }
+}
+
+/// A temporary file removed when dropped.
+pub struct TempFile(PathBuf);
+
+impl AsRef<OsStr> for TempFile {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut content = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut content);
+        }
+        content
+    })
+}
+
+/// Runs the command and returns its output, killing it if it doesn't finish in time.
+pub fn pexec(cmd: &mut Command) -> StringResult {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => std::io::Error::new(
+                e.kind(),
+                format!("{} is not found", cmd.get_program().to_string_lossy()),
+            ),
+            _ => e,
+        })?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > PROBE_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The readers are not joined as the grandchildren could still hold the pipes
+            return Err(Box::new(SuiteError::timeout(cmd, PROBE_TIMEOUT)));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() {
+        return Err(Box::new(SuiteError::process(cmd, status, &stderr)));
+    }
+    Ok(stdout)
+}
+
+/// Writes the content into the temporary file unique for this process.
+pub fn cat(filename: &str, content: &str) -> Result<TempFile, Box<dyn Error>> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("{}-{filename}", std::process::id()));
+    std::fs::write(&path, content)?;
+    Ok(TempFile(path))
+}
+
+pub fn to_result(opt: Option<String>) -> StringResult {
+    opt.ok_or_else(|| {
+        Box::new(SuiteError::ValueIsEmpty {
+            what: String::from("version"),
+        }) as Box<dyn Error>
+    })
+}
+
+/// Returns the locked version of the crate from the Cargo.lock file (the first one if several
+/// versions of the crate are locked).
+pub fn cargo_lock(path: &str, name: &str) -> StringResult {
+    let content = std::fs::read_to_string(path)?;
+    let re = Regex::new(&format!(
+        r#"name = "{}"\nversion = "([^"]+)""#,
+        regex::escape(name)
+    ))?;
+    to_result(
+        re.captures(&content)
+            .and_then(|caps| caps.get(1).map(|m| m.as_str().to_string())),
+    )
+}
+
+/// Returns the version of the dub package: the selected one if the project has been built,
+/// or the version specification from dub.json otherwise.
+pub fn dub(root: &str, name: &str) -> StringResult {
+    let selections = std::fs::read_to_string(format!("{root}/dub.selections.json"));
+    if let Ok(content) = selections {
+        let json: serde_json::Value = serde_json::from_str(&content)?;
+        if let Some(version) = json["versions"][name].as_str() {
+            return Ok(version.to_string());
+        }
+    }
+    let content = std::fs::read_to_string(format!("{root}/dub.json"))?;
+    let json: serde_json::Value = serde_json::from_str(&content)?;
+    to_result(
+        json["dependencies"][name]
+            .as_str()
+            .map(|x| format!("{x} (not built)")),
+    )
+}
+
+/// Returns the version from the Maven coordinates (`group:artifact:version`) list.
+pub fn maven(path: &str, artifact: &str) -> StringResult {
+    let content = std::fs::read_to_string(path)?;
+    to_result(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .find_map(|line| {
+                let mut parts = line.split(':');
+                parts.nth(1).filter(|x| *x == artifact)?;
+                parts.next().map(String::from)
+            }),
+    )
+}
+
+fn openswoole() -> StringResult {
+    let version = pexec(Command::new("php").args(["-r", "echo phpversion('openswoole');"]))?;
+    to_result(Some(version).filter(|x| !x.trim().is_empty()))
+}
+
+fn twisted() -> StringResult {
+    pexec(Command::new("pypy3").args(["-c", "import twisted; print(twisted.__version__)"]))
+}
+
+/// Returns the path of the executable found in PATH.
+pub fn which(name: &str) -> StringResult {
+    use std::os::unix::fs::PermissionsExt;
+
+    let paths = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|path| {
+            path.metadata()
+                .is_ok_and(|x| x.is_file() && x.permissions().mode() & 0o111 != 0)
+        })
+        .map(|path| path.display().to_string())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{name} is not found in PATH"),
+            )
+            .into()
+        })
+}
+
+/// The probes for the tools required by the benchmark runner: the executables,
+/// and the runtime extensions.
+pub fn requirements() -> BTreeMap<&'static str, ProbeFn> {
+    let mut requirements: BTreeMap<&str, ProbeFn> = BTreeMap::new();
+    for name in [
+        "cargo", "crystal", "dub", "go", "hey", "ldc2", "lsof", "node", "php", "pypy3",
+    ] {
+        requirements.insert(name, Box::new(move || which(name)));
+    }
+    requirements.insert("openswoole", Box::new(openswoole));
+    requirements.insert("twisted", Box::new(twisted));
+    requirements
+}
+
+/// Runs all probes concurrently.
+pub fn run_all<K: Ord + Clone + Send + Sync>(
+    probes: &BTreeMap<K, ProbeFn>,
+) -> BTreeMap<K, Outcome> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = probes
+            .iter()
+            .map(|(key, probe)| (key.clone(), scope.spawn(move || Outcome::new(probe()))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|(key, handle)| (key, handle.join().unwrap()))
+            .collect()
+    })
+}
+
+/// The language toolchain probes.
+pub fn languages() -> BTreeMap<&'static str, ProbeFn> {
+    let mut langs: BTreeMap<&str, ProbeFn> = BTreeMap::new();
+    langs.insert(
+        "C{pp}/g{pp}",
+        Box::new(|| pexec(Command::new("g++").args(["-dumpfullversion"]))),
+    );
+    langs.insert(
+        "Rust",
+        Box::new(|| {
+            let text = pexec(Command::new("rustc").args(["--version"]))?;
+            to_result(text.split_whitespace().nth(1).map(String::from))
+        }),
+    );
+    langs.insert(
+        "D/ldc2",
+        Box::new(|| {
+            let text = pexec(Command::new("ldc2").args(["--version"]))?;
+            LDC_PATTERN.with(|re| {
+                to_result(
+                    re.captures(&text)
+                        .and_then(|caps| caps.get(1).map(|m| m.as_str().to_string())),
+                )
+            })
+        }),
+    );
+    langs.insert(
+        "Go",
+        Box::new(|| {
+            let prog = r#"
+package main
+import (
+  "fmt"
+  "runtime"
+)
+func main() {
+  fmt.Printf(runtime.Version())
+}
+"#;
+            let file = cat("go.go", prog)?;
+            pexec(Command::new("go").arg("run").arg(&file))
+        }),
+    );
+    langs.insert(
+        "Scala",
+        Box::new(|| pexec(Command::new("scala").args(["version", "--scala"]))),
+    );
+    langs.insert(
+        "Java",
+        Box::new(|| {
+            let prog = r#"
+class Test {
+  public static void main(String[] argv) {
+    System.out.print(System.getProperty("java.version"));
+  }
+}
+"#;
+            let file = cat("java.java", prog)?;
+            pexec(Command::new("java").arg(&file))
+        }),
+    );
+    langs.insert(
+        "Node.js",
+        Box::new(|| pexec(Command::new("node").args(["-e", "console.log(process.version)"]))),
+    );
+    langs.insert(
+        "Crystal",
+        Box::new(|| pexec(Command::new("crystal").args(["eval", "puts Crystal::VERSION"]))),
+    );
+    langs.insert(
+        "PyPy",
+        Box::new(|| {
+            let prog = r#"
+import platform, sys
+pypy = "%d.%d.%d-%s%d" % sys.pypy_version_info
+print("%s for Python %s" % (pypy, platform.python_version()))
+"#;
+            let file = cat("pypy.py", prog)?;
+            pexec(Command::new("pypy3").arg(&file))
+        }),
+    );
+    langs.insert(
+        "PHP",
+        Box::new(|| pexec(Command::new("php").args(["-r", "echo phpversion();"]))),
+    );
+    langs
+}
+
+/// The framework probes, keyed by the language and the framework name.
+pub fn frameworks() -> BTreeMap<(&'static str, &'static str), ProbeFn> {
+    let mut frameworks: BTreeMap<(&str, &str), ProbeFn> = BTreeMap::new();
+    frameworks.insert(
+        ("C{pp}", "Boost.Beast"),
+        Box::new(|| {
+            let text = std::fs::read_to_string("/usr/include/boost/version.hpp")?;
+            BOOST_PATTERN.with(|re| {
+                to_result(
+                    re.captures(&text)
+                        .and_then(|caps| caps.get(1).map(|m| m.as_str().replace('_', "."))),
+                )
+            })
+        }),
+    );
+    frameworks.insert(
+        ("Crystal", "HTTP::Server"),
+        Box::new(|| Ok(STANDARD_LIBRARY.into())),
+    );
+    frameworks.insert(
+        ("D", "Serverino"),
+        Box::new(|| dub("d/serverino", "serverino")),
+    );
+    frameworks.insert(("D", "Vibe.D"), Box::new(|| dub("d/vibed", "vibe-http")));
+    frameworks.insert(("Go", "net/http"), Box::new(|| Ok(STANDARD_LIBRARY.into())));
+    frameworks.insert(
+        ("Java", "Spring Boot"),
+        Box::new(|| maven("java/Maven.list", "spring-boot-starter-web")),
+    );
+    frameworks.insert(
+        ("Node.js", "http"),
+        Box::new(|| Ok(STANDARD_LIBRARY.into())),
+    );
+    frameworks.insert(("PHP", "Open Swoole"), Box::new(openswoole));
+    frameworks.insert(("PyPy", "Twisted"), Box::new(twisted));
+    frameworks.insert(
+        ("Rust", "Actix"),
+        Box::new(|| cargo_lock("rust/actix-web/Cargo.lock", "actix-web")),
+    );
+    frameworks.insert(
+        ("Rust", "hyper (monoio)"),
+        Box::new(|| {
+            let hyper = cargo_lock("rust/hyper-monoio/Cargo.lock", "hyper")?;
+            let monoio = cargo_lock("rust/hyper-monoio/Cargo.lock", "monoio")?;
+            Ok(format!("{hyper} (monoio {monoio})"))
+        }),
+    );
+    frameworks.insert(
+        ("Rust", "hyper (tokio)"),
+        Box::new(|| {
+            let hyper = cargo_lock("rust/hyper-tokio/Cargo.lock", "hyper")?;
+            let tokio = cargo_lock("rust/hyper-tokio/Cargo.lock", "tokio")?;
+            Ok(format!("{hyper} (tokio {tokio})"))
+        }),
+    );
+    frameworks.insert(
+        ("Rust", "Rocket"),
+        Box::new(|| cargo_lock("rust/rocket/Cargo.lock", "rocket")),
+    );
+    frameworks.insert(
+        ("Rust", "Tide"),
+        Box::new(|| cargo_lock("rust/tide/Cargo.lock", "tide")),
+    );
+    frameworks.insert(
+        ("Rust", "warp"),
+        Box::new(|| cargo_lock("rust/warp/Cargo.lock", "warp")),
+    );
+    frameworks.insert(
+        ("Scala", "Akka HTTP"),
+        Box::new(|| maven("scala/Maven.list", "akka-http_3")),
+    );
+    frameworks
+}
+
+type LanguageOutcomes = BTreeMap<&'static str, Outcome>;
+type FrameworkOutcomes = BTreeMap<(&'static str, &'static str), Outcome>;
+
+/// Runs the language and the framework probes concurrently.
+pub fn run_versions() -> (LanguageOutcomes, FrameworkOutcomes) {
+    thread::scope(|scope| {
+        let frameworks = scope.spawn(|| run_all(&frameworks()));
+        (run_all(&languages()), frameworks.join().unwrap())
+    })
+}
+
+/// Builds the table from the probe outcomes: with the status column, or with the successful
+/// probes only (for the README).
+fn table<'a>(
+    columns: &[&str],
+    outcomes: impl Iterator<Item = (Vec<&'a str>, &'a Outcome)>,
+    with_status: bool,
+) -> Table {
+    let mut columns = columns.to_vec();
+    if with_status {
+        columns.push("Status");
+    }
+    let mut table = Table::new(&columns);
+    for (key, outcome) in outcomes {
+        if !with_status && outcome.status != Status::Ok {
+            continue;
+        }
+        let mut row: Vec<String> = key.iter().map(|x| x.to_string()).collect();
+        row.push(outcome.version.clone().unwrap_or_default());
+        if with_status {
+            row.push(outcome.status.to_string());
+        }
+        table.push(row);
+    }
+    table
+}
+
+/// Builds the language and the framework version tables.
+pub fn tables(
+    langs: &LanguageOutcomes,
+    frameworks: &FrameworkOutcomes,
+    with_status: bool,
+) -> (Table, Table) {
+    (
+        table(
+            &["Language", "Version"],
+            langs.iter().map(|(name, outcome)| (vec![*name], outcome)),
+            with_status,
+        ),
+        table(
+            &["Language", "Framework", "Version"],
+            frameworks
+                .iter()
+                .map(|((lang, name), outcome)| (vec![*lang, *name], outcome)),
+            with_status,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(file: &TempFile) -> &str {
+        file.0.to_str().unwrap()
+    }
+
+    #[test]
+    fn cargo_lock_takes_first_version() {
+        let file = cat(
+            "test-Cargo.lock",
+            r#"[[package]]
+name = "hyper"
+version = "0.14.28"
+
+[[package]]
+name = "hyper-util"
+version = "0.1.3"
+
+[[package]]
+name = "hyper"
+version = "1.2.0"
+"#,
+        )
+        .unwrap();
+        assert_eq!(cargo_lock(path(&file), "hyper").unwrap(), "0.14.28");
+        assert_eq!(cargo_lock(path(&file), "hyper-util").unwrap(), "0.1.3");
+        assert!(cargo_lock(path(&file), "tokio").is_err());
+    }
+
+    #[test]
+    fn dub_prefers_selections() {
+        let root = std::env::temp_dir().join(format!("{}-test-dub", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let dir = root.to_str().unwrap();
+        std::fs::write(
+            root.join("dub.json"),
+            r#"{"dependencies": {"vibe-d": "~>0.9", "mir": "~>3.2"}}"#,
+        )
+        .unwrap();
+        // The project has not been built yet
+        assert_eq!(dub(dir, "vibe-d").unwrap(), "~>0.9 (not built)");
+        std::fs::write(
+            root.join("dub.selections.json"),
+            r#"{"fileVersion": 1, "versions": {"vibe-d": "0.9.8"}}"#,
+        )
+        .unwrap();
+        assert_eq!(dub(dir, "vibe-d").unwrap(), "0.9.8");
+        assert_eq!(dub(dir, "mir").unwrap(), "~>3.2 (not built)");
+        assert!(dub(dir, "arsd").is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn maven_skips_comments() {
+        let file = cat(
+            "test-maven.txt",
+            "# io.netty:netty-all:4.0.0\n  # io.netty:netty-all:4.1.0\n\nio.netty:netty-all:4.1.107.Final\n",
+        )
+        .unwrap();
+        assert_eq!(maven(path(&file), "netty-all").unwrap(), "4.1.107.Final");
+        assert!(maven(path(&file), "vertx-core").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::tables::{escape_html, Table};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 1000px; color: #222; }
+h1 { border-bottom: 1px solid #ccc; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+th { background: #f4f4f4; }
+.chart { margin: 1em 0; }
+.failure { color: #b00; white-space: pre-wrap; font-family: monospace; }
+";
+
+/// A single static HTML page with the results, embedding the charts as the inline SVG, so it
+/// could be shared without any external assets.
+pub struct Report {
+    title: String,
+    sections: Vec<String>,
+}
+
+impl Report {
+    pub fn new(title: &str) -> Report {
+        Report {
+            title: title.to_string(),
+            sections: Vec::new(),
+        }
+    }
+
+    fn section(&mut self, heading: &str, body: String) {
+        self.sections
+            .push(format!("<h2>{}</h2>\n{body}", escape_html(heading)));
+    }
+
+    /// Adds the SVG markup (e.g. rendered with `SVGBackend::with_string`).
+    pub fn chart(&mut self, heading: &str, svg: String) {
+        self.section(heading, format!("<div class=\"chart\">\n{svg}\n</div>"));
+    }
+
+    pub fn table(&mut self, heading: &str, table: &Table) {
+        self.section(heading, table.html());
+    }
+
+    /// Adds the preformatted notes, e.g. the errors.
+    pub fn failures(&mut self, heading: &str, notes: &[(String, String)]) {
+        let body = notes
+            .iter()
+            .map(|(title, text)| {
+                format!(
+                    "<h3>{}</h3>\n<div class=\"failure\">{}</div>",
+                    escape_html(title),
+                    escape_html(text)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.section(heading, body);
+    }
+
+    pub fn html(&self) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{}\n</body>\n</html>\n",
+            self.sections.join("\n"),
+            title = escape_html(&self.title)
+        )
+    }
+}
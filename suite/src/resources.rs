@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::affinity::CpuSet;
+use crate::host::read;
+
+/// The interval is long enough not to load the host with the `/proc` scans during the benchmark.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The resources used by a process tree.
+#[derive(Clone, Copy, Default)]
+pub struct Usage {
+    /// The CPU time used so far (user and system).
+    pub cpu_seconds: f64,
+    /// The resident memory.
+    pub rss_mb: f64,
+}
+
+/// Returns the process name, the parent pid and the CPU time (in clock ticks) used by the process
+/// so far.
+fn stat(pid: u32) -> Option<(String, u32, u64)> {
+    let stat = read(&format!("/proc/{pid}/stat"))?;
+    // The name could contain spaces and parentheses, so the fields are parsed after the last ')'
+    let (head, tail) = stat.rsplit_once(')')?;
+    let name = head.split_once('(')?.1.to_string();
+    let fields: Vec<&str> = tail.split_whitespace().collect();
+    let ppid: u32 = fields.get(1)?.parse().ok()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some((name, ppid, utime + stime))
+}
+
+/// Returns the process name and the CPU time (in clock ticks) used by the process so far.
+pub fn cpu_time(pid: u32) -> Option<(String, u64)> {
+    stat(pid).map(|(name, _, ticks)| (name, ticks))
+}
+
+pub fn ticks_per_second() -> f64 {
+    unsafe { libc::sysconf(libc::_SC_CLK_TCK) as f64 }
+}
+
+fn rss_bytes(pid: u32) -> Option<u64> {
+    let statm = read(&format!("/proc/{pid}/statm"))?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+    Some(pages * page_size)
+}
+
+pub fn pids() -> Vec<u32> {
+    fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|x| x.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sums the usage of the process and all its descendants (e.g. the worker processes), or returns
+/// `None` if the process is gone.
+pub fn tree_usage(root: u32) -> Option<Usage> {
+    let stats: Vec<(u32, u32, u64)> = pids()
+        .into_iter()
+        .filter_map(|pid| stat(pid).map(|(_, ppid, ticks)| (pid, ppid, ticks)))
+        .collect();
+    if !stats.iter().any(|(pid, _, _)| *pid == root) {
+        return None;
+    }
+    let mut tree = vec![root];
+    // The processes are not sorted by the parents, so the tree is expanded until it stops growing
+    loop {
+        let size = tree.len();
+        for (pid, ppid, _) in &stats {
+            if tree.contains(ppid) && !tree.contains(pid) {
+                tree.push(*pid);
+            }
+        }
+        if tree.len() == size {
+            break;
+        }
+    }
+    let ticks: u64 = stats
+        .iter()
+        .filter(|(pid, _, _)| tree.contains(pid))
+        .map(|(_, _, ticks)| ticks)
+        .sum();
+    let rss: u64 = tree.iter().filter_map(|pid| rss_bytes(*pid)).sum();
+    Some(Usage {
+        cpu_seconds: ticks as f64 / ticks_per_second(),
+        rss_mb: rss as f64 / 1024.0 / 1024.0,
+    })
+}
+
+/// The resources used by the server during a measured run.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ServerUsage {
+    /// The CPU time used during the run (user and system).
+    pub cpu_seconds: f64,
+    /// The maximum resident memory sampled during the run.
+    pub peak_rss_mb: f64,
+}
+
+impl ServerUsage {
+    /// The number of the requests served per CPU-second.
+    pub fn requests_per_cpu_second(&self, requests: usize) -> Option<f64> {
+        (self.cpu_seconds > 0.0).then(|| requests as f64 / self.cpu_seconds)
+    }
+
+    /// The throughput per MB of the peak resident memory.
+    pub fn rps_per_mb(&self, rps: f64) -> Option<f64> {
+        (self.peak_rss_mb > 0.0).then(|| rps / self.peak_rss_mb)
+    }
+}
+
+/// Samples the resources of the process tree in the background until stopped.
+pub struct Sampler {
+    stopped: Arc<AtomicBool>,
+    handle: JoinHandle<Option<ServerUsage>>,
+}
+
+impl Sampler {
+    /// Starts the sampling thread, pinned to the CPUs (e.g. the load generator ones) if set.
+    pub fn start(pid: u32, cpus: Option<CpuSet>) -> Sampler {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let flag = stopped.clone();
+        let handle = thread::spawn(move || {
+            if let Some(cpus) = cpus {
+                cpus.pin_current_thread();
+            }
+            let first = tree_usage(pid)?;
+            let mut last = first;
+            let mut peak_rss_mb = first.rss_mb;
+            loop {
+                let done = flag.load(Ordering::Relaxed);
+                if let Some(usage) = tree_usage(pid) {
+                    peak_rss_mb = peak_rss_mb.max(usage.rss_mb);
+                    last = usage;
+                }
+                // The last sample is taken after the stop, to include the whole run
+                if done {
+                    break;
+                }
+                thread::park_timeout(SAMPLE_INTERVAL);
+            }
+            Some(ServerUsage {
+                cpu_seconds: last.cpu_seconds - first.cpu_seconds,
+                peak_rss_mb,
+            })
+        });
+        Sampler { stopped, handle }
+    }
+
+    /// Stops the sampling, and returns the usage (or `None` if the process has been gone).
+    pub fn stop(self) -> Option<ServerUsage> {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.handle.thread().unpark();
+        self.handle.join().ok().flatten()
+    }
+}
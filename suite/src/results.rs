@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::preflight::Snapshot;
+use crate::resources::ServerUsage;
+use crate::scaling::ScalingPoint;
+use crate::scenarios::Scenario;
+use crate::stats::Sample;
+
+/// The stage of the benchmark a language failed at.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Build,
+    Start,
+    Load,
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = match self {
+            Phase::Build => "build failure",
+            Phase::Start => "start failure",
+            Phase::Load => "load failure",
+        };
+        write!(f, "{text}")
+    }
+}
+
+#[derive(Serialize)]
+pub struct Failure {
+    pub title: String,
+    pub phase: Phase,
+    pub error: String,
+}
+
+/// The samples of a scenario run.
+#[derive(Serialize, Deserialize)]
+pub struct ScenarioRun {
+    /// The name of the scenario in the registry, e.g. `index`.
+    pub scenario: String,
+    pub samples: Vec<Sample>,
+    /// The resources used by the server during the measured run (if sampled).
+    pub usage: Option<ServerUsage>,
+}
+
+/// The samples of a language run.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Measurement {
+    /// In the order of the scenario registry.
+    pub scenarios: Vec<ScenarioRun>,
+    /// The number of the error lines in the server output during the measurement.
+    pub server_errors: usize,
+    /// The problems not failing the measurement, e.g. an additional chart failed to be saved.
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+/// The results of a completed language saved in the run directory, e.g. `results/<run>/go.json`,
+/// so an interrupted run could be resumed.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub measurement: Measurement,
+    /// Empty unless the run is in the scaling mode.
+    pub scaling: Vec<ScalingPoint>,
+}
+
+fn checkpoint_file(dir: &Path, lang: &str) -> PathBuf {
+    dir.join(format!("{lang}.json"))
+}
+
+impl Checkpoint {
+    /// Keeps only the results of the scenarios, returns false if any of them is missing.
+    pub fn retain(&mut self, scenarios: &[&Scenario]) -> bool {
+        let selected = |name: &str| scenarios.iter().any(|x| x.name == name);
+        self.measurement.scenarios.retain(|x| selected(&x.scenario));
+        for point in &mut self.scaling {
+            point.rps.retain(|name, _| selected(name));
+        }
+        self.measurement.scenarios.len() == scenarios.len()
+    }
+
+    pub fn save(&self, dir: &Path, lang: &str) -> Result<(), Box<dyn Error>> {
+        // Write to the temporary file first, so an interruption doesn't leave a broken checkpoint
+        let file = checkpoint_file(dir, lang);
+        let tmp_file = file.with_extension("json.tmp");
+        fs::write(&tmp_file, serde_json::to_string(self)?)?;
+        fs::rename(&tmp_file, &file)?;
+        Ok(())
+    }
+
+    /// Returns the saved results of the language, if any.
+    pub fn load(dir: &Path, lang: &str) -> Result<Option<Checkpoint>, Box<dyn Error>> {
+        let file = checkpoint_file(dir, lang);
+        if !file.try_exists()? {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&file)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+}
+
+/// The results of the run all the outputs are produced from.
+#[derive(Default)]
+pub struct Results<'a> {
+    /// The measured languages by title, in the order of the run.
+    pub measured: Vec<(&'a str, Measurement)>,
+    pub failures: Vec<Failure>,
+    /// Empty unless the run is in the scaling mode.
+    pub scaling: Vec<ScalingPoint>,
+    /// The state of the host right before the benchmarks of this session.
+    pub preflight: Option<Snapshot>,
+}
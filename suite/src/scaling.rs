@@ -0,0 +1,213 @@
+use itertools::Itertools;
+use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::chart::Theme;
+use crate::host::HostInfo;
+use crate::scenarios;
+
+/// Throughput of a single target restricted to the number of cores.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScalingPoint {
+    pub title: String,
+    pub cores: usize,
+    /// The throughput by the scenario name.
+    pub rps: BTreeMap<String, f64>,
+}
+
+/// Returns the core counts to test: the powers of two up to the maximum, and the maximum itself.
+pub fn core_counts(max: usize) -> Vec<usize> {
+    let mut result: Vec<usize> = (0..).map(|x| 1 << x).take_while(|x| *x < max).collect();
+    result.push(max);
+    result
+}
+
+fn titles(points: &[ScalingPoint]) -> Vec<&str> {
+    points.iter().map(|x| x.title.as_str()).unique().collect()
+}
+
+/// Returns the measured scenarios in the order of the registry.
+fn scenarios(points: &[ScalingPoint]) -> Vec<&str> {
+    points
+        .iter()
+        .flat_map(|x| x.rps.keys().map(|name| name.as_str()))
+        .unique()
+        .sorted_by_key(|name| scenarios::describe(name).0)
+        .collect()
+}
+
+/// Renders the AsciiDoc table with the parallel efficiency: the throughput on N cores
+/// divided by N times the single-core throughput.
+pub fn efficiency_table(points: &[ScalingPoint], host: &HostInfo) -> String {
+    let counts: Vec<usize> = points.iter().map(|x| x.cores).unique().sorted().collect();
+    let mut table = vec![
+        host.asciidoc(),
+        format!("[cols=\"{}*\"]", counts.len() + 2),
+        "|===".to_string(),
+        format!(
+            "| Language | Scenario {}",
+            counts.iter().map(|x| format!("| {x} cores")).join(" ")
+        ),
+    ];
+    for title in titles(points) {
+        let base = points.iter().find(|x| x.title == title && x.cores == 1);
+        for name in scenarios(points) {
+            let rps = |x: &ScalingPoint| x.rps.get(name).copied();
+            let cells: Vec<String> = counts
+                .iter()
+                .map(|cores| {
+                    let point = points
+                        .iter()
+                        .find(|x| x.title == title && x.cores == *cores)
+                        .and_then(rps);
+                    match (point, base.and_then(rps)) {
+                        (Some(point), Some(base)) if base > 0.0 => format!(
+                            "| {point:.0} rps, {:.0}%",
+                            100.0 * point / (base * *cores as f64)
+                        ),
+                        (Some(point), _) => format!("| {point:.0} rps"),
+                        _ => "| -".to_string(),
+                    }
+                })
+                .collect();
+            let scenario = scenarios::describe(name).1;
+            table.push(format!("\n| {title} | {scenario} {}", cells.join(" ")));
+        }
+    }
+    table.push("|===".to_string());
+    table.join("\n")
+}
+
+pub fn draw_scaling<DB: DrawingBackend>(
+    points: &[ScalingPoint],
+    host: &HostInfo,
+    theme: Theme,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let max_cores = points.iter().map(|x| x.cores).max().unwrap_or(1);
+    let max_rps = points
+        .iter()
+        .flat_map(|x| x.rps.values().copied())
+        .fold(0.0, f64::max);
+    let names = scenarios(points);
+    // The same color for the language in every panel, regardless of the order it's been run in
+    let sorted_titles: Vec<&str> = titles(points).into_iter().sorted().collect();
+    let lang_idx = |title| sorted_titles.iter().position(|x| *x == title).unwrap_or(0);
+
+    let root = backend.into_drawing_area();
+    let foreground = theme.foreground();
+    root.fill(&theme.background())?;
+    let root = host.footer(&root, &foreground)?;
+    let areas = root.split_evenly((1, names.len().max(1)));
+    for (area, name) in areas.iter().zip(names) {
+        let mut chart = ChartBuilder::on(area)
+            .caption(
+                scenarios::describe(name).1,
+                ("sans-serif", 16).into_font().color(&foreground),
+            )
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(1..max_cores.max(2), 0.0..max_rps * 1.1)?;
+        chart
+            .configure_mesh()
+            .x_desc("Cores")
+            .y_desc("Requests/s")
+            .x_labels(max_cores)
+            .axis_style(foreground)
+            .label_style(("sans-serif", 12).into_font().color(&foreground))
+            .axis_desc_style(("sans-serif", 14).into_font().color(&foreground))
+            .bold_line_style(theme.grid())
+            .light_line_style(theme.background())
+            .draw()?;
+        for title in titles(points) {
+            let color = Palette99::pick(lang_idx(title));
+            chart
+                .draw_series(LineSeries::new(
+                    points
+                        .iter()
+                        .filter(|x| x.title == title)
+                        .filter_map(|x| Some((x.cores, *x.rps.get(name)?))),
+                    color.stroke_width(2),
+                ))?
+                .label(title)
+                .legend(move |(x, y)| {
+                    PathElement::new([(x, y), (x + 12, y)], color.stroke_width(2))
+                });
+        }
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .label_font(("sans-serif", 12).into_font().color(&foreground))
+            .background_style(theme.background().mix(0.8).filled())
+            .border_style(foreground.mix(0.5))
+            .draw()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> HostInfo {
+        HostInfo {
+            cpu_model: "Test CPU".to_string(),
+            cores: 4,
+            mem_total_mb: 1024,
+            kernel: "6.1".to_string(),
+            distro: "Test OS".to_string(),
+        }
+    }
+
+    fn point(cores: usize, rps: f64) -> ScalingPoint {
+        ScalingPoint {
+            title: "Go".to_string(),
+            cores,
+            rps: BTreeMap::from([("index".to_string(), rps)]),
+        }
+    }
+
+    fn rows(points: &[ScalingPoint]) -> Vec<String> {
+        efficiency_table(points, &host())
+            .lines()
+            .filter(|x| x.starts_with("| Go"))
+            .map(|x| x.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn core_counts_are_powers_of_two_and_maximum() {
+        assert_eq!(core_counts(1), vec![1]);
+        assert_eq!(core_counts(4), vec![1, 2, 4]);
+        assert_eq!(core_counts(6), vec![1, 2, 4, 6]);
+    }
+
+    #[test]
+    fn efficiency_relative_to_single_core() {
+        let points = [point(1, 1000.0), point(2, 1500.0), point(4, 4000.0)];
+        let table = efficiency_table(&points, &host());
+        assert!(table.contains("| Language | Scenario | 1 cores | 2 cores | 4 cores"));
+        assert_eq!(
+            rows(&points),
+            ["| Go | Index URL Request | 1000 rps, 100% | 1500 rps, 75% | 4000 rps, 100%"]
+        );
+    }
+
+    #[test]
+    fn efficiency_without_baseline() {
+        // Only the throughput is shown if the single-core run is missing or failed
+        let missing = [point(2, 1500.0), point(4, 4000.0)];
+        assert_eq!(
+            rows(&missing),
+            ["| Go | Index URL Request | 1500 rps | 4000 rps"]
+        );
+        let zero = [point(1, 0.0), point(2, 1500.0)];
+        assert_eq!(rows(&zero), ["| Go | Index URL Request | 0 rps | 1500 rps"]);
+    }
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// The address all the servers listen on.
+pub const ADDRESS: &str = "127.0.0.1:3000";
+
+/// A workload the servers are measured with.
+pub struct Scenario {
+    /// The name to select the scenario by, and to tag the log messages with.
+    pub name: &'static str,
+    pub title: &'static str,
+    pub method: &'static str,
+    pub path: &'static str,
+    /// The text the response body of every server must contain (case-sensitive, the servers
+    /// differ in the details, e.g. "Hello, hello", "Hello hello!" and "Hello hello").
+    pub expected: &'static str,
+}
+
+/// The registry of the scenarios, the position of a scenario picks its color on the charts.
+pub const SCENARIOS: [Scenario; 2] = [
+    Scenario {
+        name: "index",
+        title: "Index URL Request",
+        method: "GET",
+        path: "/",
+        expected: "Hello",
+    },
+    Scenario {
+        name: "pattern",
+        title: "Pattern URL Request",
+        method: "GET",
+        path: "/greeting/hello",
+        expected: "hello",
+    },
+];
+
+pub fn names() -> Vec<&'static str> {
+    SCENARIOS.iter().map(|x| x.name).collect()
+}
+
+/// Returns the position in the registry and the scenario by the name.
+pub fn find(name: &str) -> Option<(usize, &'static Scenario)> {
+    SCENARIOS.iter().enumerate().find(|(_, x)| x.name == name)
+}
+
+/// Returns the position in the registry and the title of the scenario (the unknown scenarios,
+/// e.g. removed since the results have been saved, are placed last and titled by the name).
+pub fn describe(name: &str) -> (usize, &str) {
+    find(name).map_or((SCENARIOS.len(), name), |(idx, x)| (idx, x.title))
+}
+
+impl Scenario {
+    pub fn url(&self) -> String {
+        format!("http://{ADDRESS}{}", self.path)
+    }
+
+    /// Sends a single request, and checks the server responds as expected.
+    pub fn check(&self, timeout: Duration) -> Result<(), Box<dyn Error>> {
+        let mut stream = TcpStream::connect(ADDRESS)?;
+        stream.set_read_timeout(Some(timeout))?;
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {ADDRESS}\r\nConnection: close\r\n\r\n",
+            self.method, self.path
+        )?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let response = String::from_utf8_lossy(&response);
+        let status = response.lines().next().unwrap_or_default();
+        let body = response.split_once("\r\n\r\n").map_or("", |x| x.1);
+        if !status
+            .split_whitespace()
+            .nth(1)
+            .is_some_and(|x| x.starts_with('2'))
+        {
+            return Err(format!(
+                "Unexpected response to {} {}: {status}",
+                self.method, self.path
+            )
+            .into());
+        }
+        if !body.contains(self.expected) {
+            return Err(format!(
+                "Unexpected response to {} {}: expected '{}' in '{}'",
+                self.method,
+                self.path,
+                self.expected,
+                body.trim()
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use regex::Regex;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use crate::errors;
+
+thread_local! {
+    static ERROR_PATTERN: Regex =
+        Regex::new(r"(?i)\b(error|exception|panic(ked)?|fatal|traceback)\b").unwrap();
+}
+
+/// The file the server stdout and stderr are redirected to, e.g. `results/<run>/go.log`.
+#[derive(Clone)]
+pub struct ServerLog {
+    pub path: PathBuf,
+}
+
+impl ServerLog {
+    pub fn new(dir: &Path, lang: &str) -> ServerLog {
+        ServerLog {
+            path: dir.join(format!("{lang}.log")),
+        }
+    }
+
+    /// Starts the log from scratch (e.g. discarding the output of the interrupted run).
+    pub fn reset(&self) -> Result<(), Box<dyn Error>> {
+        File::create(&self.path)?;
+        Ok(())
+    }
+
+    /// Returns the stdout and stderr appending to the log (the servers could be started
+    /// several times, e.g. in the scaling mode).
+    pub fn stdio(&self) -> Result<(Stdio, Stdio), Box<dyn Error>> {
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok((Stdio::from(file.try_clone()?), Stdio::from(file)))
+    }
+
+    /// The current size, used as the starting point for `count_errors`.
+    pub fn offset(&self) -> u64 {
+        fs::metadata(&self.path).map_or(0, |x| x.len())
+    }
+
+    /// Counts the lines looking like errors written after the offset.
+    pub fn count_errors(&self, offset: u64) -> usize {
+        let mut content = String::new();
+        let read = File::open(&self.path).and_then(|mut file| {
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_to_string(&mut content)
+        });
+        if read.is_err() {
+            return 0;
+        }
+        ERROR_PATTERN.with(|re| content.lines().filter(|line| re.is_match(line)).count())
+    }
+
+    /// Returns the last lines of the log.
+    pub fn tail(&self) -> String {
+        fs::read(&self.path)
+            .map(|content| errors::stderr_tail(&String::from_utf8_lossy(&content)))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// A single request measured by hey.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Sample {
+    /// Seconds since the start of the run when the request was sent.
+    pub offset: f64,
+    /// Response time, ms.
+    pub latency: f64,
+    /// HTTP status code (the results saved before it has been recorded are considered successful).
+    #[serde(default = "ok_status")]
+    pub status: u16,
+}
+
+fn ok_status() -> u16 {
+    200
+}
+
+/// Returns the percentile (0..=100) of the already sorted values using linear interpolation.
+pub fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (pct / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let weight = rank - lower as f64;
+    sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+}
+
+pub fn sorted(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut result: Vec<f64> = values.collect();
+    result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    result
+}
+
+pub fn latencies(samples: &[Sample]) -> Vec<f64> {
+    samples.iter().map(|x| x.latency).collect()
+}
+
+/// Returns the achieved throughput: the number of requests divided by the time
+/// between the first request sent and the last response received.
+pub fn rps(samples: &[Sample]) -> f64 {
+    let start = samples
+        .iter()
+        .map(|x| x.offset)
+        .fold(f64::INFINITY, f64::min);
+    let end = samples
+        .iter()
+        .map(|x| x.offset + x.latency / 1000.0)
+        .fold(0.0, f64::max);
+    if end > start {
+        samples.len() as f64 / (end - start)
+    } else {
+        0.0
+    }
+}
+
+/// Returns the number of the failed requests: the ones without the response (hey reports only
+/// the completed requests), and the ones with the non-2xx status. hey splits the requests evenly
+/// between the workers, so it sends only a multiple of the concurrency.
+pub fn errors(samples: &[Sample], requests: u32, concurrency: u32) -> usize {
+    let sent = requests / concurrency * concurrency;
+    let missing = (sent as usize).saturating_sub(samples.len());
+    missing
+        + samples
+            .iter()
+            .filter(|x| !(200..300).contains(&x.status))
+            .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_interpolates() {
+        let values = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&values, 0.0), 1.0);
+        assert_eq!(percentile(&values, 100.0), 4.0);
+        assert_eq!(percentile(&values, 50.0), 2.5);
+        assert!((percentile(&values, 90.0) - 3.7).abs() < 1e-9);
+        assert_eq!(percentile(&[5.0], 99.0), 5.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn sorted_orders_values() {
+        assert_eq!(
+            sorted([3.0, 1.0, 2.0, 1.0].into_iter()),
+            vec![1.0, 1.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn rps_spans_first_request_to_last_response() {
+        let samples = [
+            Sample {
+                offset: 1.0,
+                latency: 500.0,
+                status: 200,
+            },
+            Sample {
+                offset: 1.5,
+                latency: 500.0,
+                status: 200,
+            },
+            Sample {
+                offset: 1.2,
+                latency: 100.0,
+                status: 200,
+            },
+        ];
+        // Three requests from 1.0 s until the last response at 2.0 s
+        assert_eq!(rps(&samples), 3.0);
+        assert_eq!(rps(&[]), 0.0);
+    }
+
+    #[test]
+    fn errors_count_missing_and_failed_responses() {
+        let sample = |status| Sample {
+            offset: 0.0,
+            latency: 1.0,
+            status,
+        };
+        let samples = [sample(200), sample(204), sample(404), sample(500)];
+        assert_eq!(errors(&samples, 4, 1), 2);
+        // Requests without the response are failed too
+        assert_eq!(errors(&samples, 6, 1), 4);
+        assert_eq!(errors(&[], 0, 1), 0);
+    }
+
+    #[test]
+    fn errors_ignore_requests_hey_does_not_send() {
+        let samples = vec![
+            Sample {
+                offset: 0.0,
+                latency: 1.0,
+                status: 200,
+            };
+            49920
+        ];
+        // 50000 requests with 256 workers are 195 requests per worker
+        assert_eq!(errors(&samples, 50000, 256), 0);
+        assert_eq!(errors(&samples[1..], 50000, 256), 1);
+    }
+}
@@ -0,0 +1,223 @@
+use serde_json::{Map, Value};
+
+/// A simple table that could be rendered in the documentation formats.
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: &[&str]) -> Table {
+        Table {
+            columns: columns.iter().map(|x| x.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Returns the copy with the AsciiDoc attributes (e.g. `C{pp}`) replaced by the plain text
+    /// for the other formats.
+    pub fn plain(&self) -> Table {
+        Table {
+            columns: self.columns.clone(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| row.iter().map(|x| x.replace("{pp}", "++")).collect())
+                .collect(),
+        }
+    }
+
+    pub fn adoc(&self) -> String {
+        let mut lines = vec![
+            "|===".to_string(),
+            format!("| {}", self.columns.join(" | ")),
+        ];
+        for row in &self.rows {
+            lines.push(String::new());
+            lines.extend(row.iter().map(|x| format!("| {x}")));
+        }
+        lines.push("|===".to_string());
+        lines.join("\n")
+    }
+
+    pub fn markdown(&self) -> String {
+        let escape = |x: &String| x.replace('|', "\\|");
+        let mut lines = vec![
+            format!(
+                "| {} |",
+                self.columns
+                    .iter()
+                    .map(escape)
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ),
+            format!("|{}", "---|".repeat(self.columns.len())),
+        ];
+        for row in &self.rows {
+            lines.push(format!(
+                "| {} |",
+                row.iter().map(escape).collect::<Vec<_>>().join(" | ")
+            ));
+        }
+        lines.join("\n")
+    }
+
+    pub fn html(&self) -> String {
+        let cells = |tag: &str, row: &[String]| {
+            row.iter()
+                .map(|x| format!("<{tag}>{}</{tag}>", escape_html(x)))
+                .collect::<String>()
+        };
+        let mut lines = vec![
+            "<table>".to_string(),
+            format!("<tr>{}</tr>", cells("th", &self.columns)),
+        ];
+        for row in &self.rows {
+            lines.push(format!("<tr>{}</tr>", cells("td", row)));
+        }
+        lines.push("</table>".to_string());
+        lines.join("\n")
+    }
+
+    /// Returns the rows as the objects with the lowercase column names as the keys.
+    pub fn json(&self) -> Value {
+        let keys: Vec<String> = self
+            .columns
+            .iter()
+            .map(|x| x.to_lowercase().replace(' ', "_"))
+            .collect();
+        Value::Array(
+            self.rows
+                .iter()
+                .map(|row| {
+                    let object: Map<String, Value> = keys
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().map(|x| Value::String(x.clone())))
+                        .collect();
+                    Value::Object(object)
+                })
+                .collect(),
+        )
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Replaces the content between the `// <name>:begin` and `// <name>:end` AsciiDoc comments.
+pub fn replace_between_markers(document: &str, name: &str, content: &str) -> Option<String> {
+    let begin = format!("// {name}:begin\n");
+    let end = format!("// {name}:end");
+    let start = document.find(&begin)? + begin.len();
+    let finish = start + document[start..].find(&end)?;
+    let content = content.trim_end();
+    // The end marker is kept on its own line, otherwise it breaks the AsciiDoc blocks
+    let separator = if content.is_empty() { "" } else { "\n" };
+    Some(format!(
+        "{}{content}{separator}{}",
+        &document[..start],
+        &document[finish..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(&["Language", "Version"]);
+        table.push(vec![String::from("C{pp}"), String::from("14.2.0")]);
+        table.push(vec![String::from("Go"), String::from("go1.25.0")]);
+        table
+    }
+
+    #[test]
+    fn replaces_between_markers() {
+        let document = "Intro\n\n// versions:begin\nold\n// versions:end\n\nOutro\n";
+        assert_eq!(
+            replace_between_markers(document, "versions", "new\n\n").unwrap(),
+            "Intro\n\n// versions:begin\nnew\n// versions:end\n\nOutro\n"
+        );
+    }
+
+    #[test]
+    fn marker_round_trip() {
+        let document = "== Environment\n\n// versions:begin\n// versions:end\n";
+        let content = table().adoc();
+        let updated = replace_between_markers(document, "versions", &content).unwrap();
+        assert!(updated.contains("\n|===\n// versions:end\n"));
+        // Updating again with the same content changes nothing
+        assert_eq!(
+            replace_between_markers(&updated, "versions", &content).unwrap(),
+            updated
+        );
+        // And the empty content restores the original document
+        assert_eq!(
+            replace_between_markers(&updated, "versions", "").unwrap(),
+            document
+        );
+    }
+
+    #[test]
+    fn leaves_other_markers_alone() {
+        let document =
+            "// results:begin\nA\n// results:end\n// versions:begin\nB\n// versions:end\n";
+        assert_eq!(
+            replace_between_markers(document, "versions", "C").unwrap(),
+            "// results:begin\nA\n// results:end\n// versions:begin\nC\n// versions:end\n"
+        );
+    }
+
+    #[test]
+    fn requires_both_markers() {
+        assert!(replace_between_markers("// versions:end\n", "versions", "x").is_none());
+        assert!(replace_between_markers("// versions:begin\n", "versions", "x").is_none());
+        // The begin marker must be on its own line
+        assert!(
+            replace_between_markers("// versions:begin // versions:end", "versions", "x").is_none()
+        );
+    }
+
+    #[test]
+    fn readme_markers_are_on_own_lines() {
+        let readme = include_str!("../../README.adoc");
+        for name in ["results", "environment", "versions"] {
+            for marker in [format!("// {name}:begin"), format!("// {name}:end")] {
+                assert_eq!(
+                    readme.lines().filter(|line| *line == marker).count(),
+                    1,
+                    "{marker} is not on its own line"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn renders_formats() {
+        let table = table();
+        assert_eq!(
+            table.adoc(),
+            "|===\n| Language | Version\n\n| C{pp}\n| 14.2.0\n\n| Go\n| go1.25.0\n|==="
+        );
+        assert_eq!(
+            table.plain().markdown(),
+            "| Language | Version |\n|---|---|\n| C++ | 14.2.0 |\n| Go | go1.25.0 |"
+        );
+        assert_eq!(
+            table.plain().json(),
+            serde_json::json!([
+                {"language": "C++", "version": "14.2.0"},
+                {"language": "Go", "version": "go1.25.0"},
+            ])
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::errors::SuiteError;
+use crate::logging;
+use crate::Cmd;
+
+/// The groups of the languages, which could be selected as a whole (e.g. `rust`).
+pub const TAGS: [(&str, &str); 6] = [
+    ("compiled", "compiled to the native code ahead of time"),
+    ("d", "the D frameworks"),
+    ("interpreted", "run by an interpreter"),
+    ("jit", "compiled just in time"),
+    ("rust", "the Rust frameworks"),
+    ("thread-per-core", "a single-threaded event loop per core"),
+];
+
+/// The languages to run.
+pub struct Selection {
+    pub langs: Vec<String>,
+    /// The languages listed explicitly, which are run even if unavailable (the ones selected by
+    /// `all`, a glob or a tag are skipped instead of failing).
+    pub named: Vec<String>,
+}
+
+impl Selection {
+    pub fn is_named(&self, lang: &str) -> bool {
+        self.named.iter().any(|x| x == lang)
+    }
+}
+
+fn is_glob(target: &str) -> bool {
+    target.contains(['*', '?'])
+}
+
+fn glob(pattern: &str) -> Regex {
+    let re = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("^{re}$")).unwrap()
+}
+
+/// The edit distance between the strings (the number of the inserted, deleted or replaced
+/// characters).
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(x != *y);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the known names close to the unknown one, the closest first.
+fn suggestions(target: &str, names: &[&str]) -> Vec<String> {
+    let limit = (target.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &str)> = names
+        .iter()
+        .map(|name| (distance(target, name), *name))
+        .filter(|(distance, name)| *distance <= limit || name.starts_with(target))
+        .collect();
+    close.sort();
+    close
+        .into_iter()
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Returns the languages the target (a key, `all`, a glob or a tag) refers to.
+fn resolve(lang_cmds: &BTreeMap<&str, Cmd>, target: &str) -> Result<Vec<String>, SuiteError> {
+    let keys = || lang_cmds.keys().map(|key| key.to_string());
+    if target == "all" {
+        return Ok(keys().collect());
+    }
+    if lang_cmds.contains_key(target) {
+        return Ok(vec![target.to_string()]);
+    }
+    if is_glob(target) {
+        let re = glob(target);
+        let langs: Vec<String> = keys().filter(|key| re.is_match(key)).collect();
+        if langs.is_empty() {
+            logging::warn(&format!("{target} doesn't match any language"));
+        }
+        return Ok(langs);
+    }
+    if TAGS.iter().any(|(tag, _)| *tag == target) {
+        let langs: Vec<String> = lang_cmds
+            .iter()
+            .filter(|(_, cmd)| cmd.tags.contains(&target))
+            .map(|(key, _)| key.to_string())
+            .collect();
+        if langs.is_empty() {
+            logging::warn(&format!("no languages are tagged {target}"));
+        }
+        return Ok(langs);
+    }
+    let names: Vec<&str> = lang_cmds
+        .keys()
+        .copied()
+        .chain(TAGS.iter().map(|(tag, _)| *tag))
+        .collect();
+    Err(SuiteError::UnknownTarget {
+        target: target.to_string(),
+        suggestions: suggestions(target, &names),
+    })
+}
+
+/// Selects the languages by the targets, excluding the ones matched by the exclusions.
+pub fn select(
+    lang_cmds: &BTreeMap<&str, Cmd>,
+    targets: &[String],
+    exclusions: &[String],
+) -> Result<Selection, SuiteError> {
+    let mut langs = Vec::new();
+    for target in targets {
+        for lang in resolve(lang_cmds, target)? {
+            if !langs.contains(&lang) {
+                langs.push(lang);
+            }
+        }
+    }
+    for exclusion in exclusions {
+        let excluded = resolve(lang_cmds, exclusion)?;
+        langs.retain(|lang| !excluded.contains(lang));
+    }
+    if langs.is_empty() {
+        return Err(SuiteError::NoLanguages);
+    }
+    let named = targets
+        .iter()
+        .filter(|x| langs.contains(x))
+        .unique()
+        .cloned()
+        .collect();
+    Ok(Selection { langs, named })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(tags: &'static [&'static str]) -> Cmd<'static> {
+        Cmd {
+            title: "",
+            requires: &[],
+            tags,
+            sources: &[],
+            artifact: None,
+            build: Box::new(|| Ok(())),
+            run: Box::new(|_| Ok(0)),
+        }
+    }
+
+    fn lang_cmds() -> BTreeMap<&'static str, Cmd<'static>> {
+        BTreeMap::from([
+            ("go", cmd(&["compiled"])),
+            ("nodejs", cmd(&["jit"])),
+            ("python", cmd(&["interpreted"])),
+            ("rust_actix", cmd(&["rust", "compiled"])),
+            ("rust_monoio", cmd(&["rust", "compiled", "thread-per-core"])),
+        ])
+    }
+
+    fn select_strs(targets: &[&str], exclusions: &[&str]) -> Result<Selection, SuiteError> {
+        let strings = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        select(&lang_cmds(), &strings(targets), &strings(exclusions))
+    }
+
+    #[test]
+    fn glob_matches_whole_name() {
+        assert!(glob("rust_*").is_match("rust_actix"));
+        assert!(glob("rust_*").is_match("rust_"));
+        assert!(!glob("rust_*").is_match("trust_actix"));
+        assert!(glob("g?").is_match("go"));
+        assert!(!glob("g?").is_match("gox"));
+        // The regex characters are literal
+        assert!(!glob("rust.*").is_match("rust_actix"));
+    }
+
+    #[test]
+    fn distance_counts_edits() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("go", "go"), 0);
+        assert_eq!(distance("", "go"), 2);
+        assert_eq!(distance("nodjs", "nodejs"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggestions_are_close_names_closest_first() {
+        let names = ["go", "nodejs", "python", "rust_actix", "rust"];
+        assert_eq!(suggestions("nodjs", &names), vec!["nodejs"]);
+        assert_eq!(suggestions("rst", &names), vec!["rust"]);
+        assert_eq!(suggestions("rust_", &names), vec!["rust", "rust_actix"]);
+        assert!(suggestions("haskell", &names).is_empty());
+    }
+
+    #[test]
+    fn select_by_names_keeps_order_without_duplicates() {
+        let selection = select_strs(&["python", "go", "python"], &[]).unwrap();
+        assert_eq!(selection.langs, vec!["python", "go"]);
+        assert_eq!(selection.named, vec!["python", "go"]);
+    }
+
+    #[test]
+    fn select_by_glob_tag_and_all() {
+        let selection = select_strs(&["rust_*"], &[]).unwrap();
+        assert_eq!(selection.langs, vec!["rust_actix", "rust_monoio"]);
+        assert!(selection.named.is_empty());
+
+        let selection = select_strs(&["compiled"], &[]).unwrap();
+        assert_eq!(selection.langs, vec!["go", "rust_actix", "rust_monoio"]);
+
+        let selection = select_strs(&["all"], &[]).unwrap();
+        assert_eq!(selection.langs.len(), 5);
+    }
+
+    #[test]
+    fn select_marks_only_explicit_names() {
+        let selection = select_strs(&["go", "rust"], &[]).unwrap();
+        assert_eq!(selection.langs, vec!["go", "rust_actix", "rust_monoio"]);
+        assert!(selection.is_named("go"));
+        assert!(!selection.is_named("rust_actix"));
+    }
+
+    #[test]
+    fn select_with_exclusions() {
+        let selection = select_strs(&["rust"], &["rust_monoio"]).unwrap();
+        assert_eq!(selection.langs, vec!["rust_actix"]);
+
+        let selection = select_strs(&["all"], &["compiled", "py*"]).unwrap();
+        assert_eq!(selection.langs, vec!["nodejs"]);
+
+        // The excluded names are not run even if listed explicitly
+        let selection = select_strs(&["go", "nodejs"], &["go"]).unwrap();
+        assert_eq!(selection.langs, vec!["nodejs"]);
+        assert!(!selection.is_named("go"));
+    }
+
+    #[test]
+    fn select_rejects_unknown_and_empty() {
+        match select_strs(&["nodjs"], &[]) {
+            Err(SuiteError::UnknownTarget {
+                target,
+                suggestions,
+            }) => {
+                assert_eq!(target, "nodjs");
+                assert_eq!(suggestions, vec!["nodejs"]);
+            }
+            _ => panic!("expected an unknown target"),
+        }
+        assert!(matches!(
+            select_strs(&["go"], &["haskell"]),
+            Err(SuiteError::UnknownTarget { .. })
+        ));
+        assert!(matches!(
+            select_strs(&["go"], &["compiled"]),
+            Err(SuiteError::NoLanguages)
+        ));
+        assert!(matches!(
+            select_strs(&["java_*"], &[]),
+            Err(SuiteError::NoLanguages)
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use plotters::prelude::*;
+use std::error::Error;
+
+use crate::chart::{scenario_color, Theme};
+use crate::host::HostInfo;
+
+use crate::stats::{percentile, sorted, Sample};
+
+/// Number of time buckets the run is split into (fast servers finish in under a second).
+const BUCKETS: usize = 100;
+const MIN_BUCKET_WIDTH: f64 = 0.01;
+/// Number of the buckets the rolling statistics are calculated over.
+const WINDOW: usize = 5;
+
+pub struct Point {
+    /// The end of the window, seconds since the start of the run.
+    pub time: f64,
+    pub median: f64,
+    pub p99: f64,
+    /// Requests sent per second within the window.
+    pub rps: f64,
+}
+
+/// Groups the samples by the time they were sent and calculates the rolling latency
+/// percentiles and the throughput over the window of the last buckets.
+pub fn series(samples: &[Sample]) -> Vec<Point> {
+    let duration = samples.iter().map(|x| x.offset).fold(0.0, f64::max);
+    let width = (duration / BUCKETS as f64).max(MIN_BUCKET_WIDTH);
+    let mut buckets = vec![Vec::new(); (duration / width) as usize + 1];
+    for sample in samples {
+        buckets[(sample.offset / width) as usize].push(sample.latency);
+    }
+    (0..buckets.len())
+        .filter(|idx| !buckets[*idx].is_empty())
+        .map(|idx| {
+            let window = &buckets[(idx + 1).saturating_sub(WINDOW)..=idx];
+            let values = sorted(window.iter().flatten().copied());
+            Point {
+                time: (idx + 1) as f64 * width,
+                median: percentile(&values, 50.0),
+                p99: percentile(&values, 99.0),
+                rps: values.len() as f64 / (window.len() as f64 * width),
+            }
+        })
+        .collect()
+}
+
+/// Draws the latency and throughput lines of every scenario (with the position of the scenario
+/// in the registry, which picks the color).
+pub fn draw_timeline<DB: DrawingBackend>(
+    title: &str,
+    scenarios: &[(usize, &str, Vec<Point>)],
+    host: &HostInfo,
+    theme: Theme,
+    backend: DB,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let points = || scenarios.iter().flat_map(|x| x.2.iter());
+    let max_time = points().map(|x| x.time).fold(0.0, f64::max);
+    let max_latency = points().map(|x| x.p99).fold(0.0, f64::max);
+    let max_rps = points().map(|x| x.rps).fold(0.0, f64::max);
+
+    let root = backend.into_drawing_area();
+    let foreground = theme.foreground();
+    root.fill(&theme.background())?;
+    let root = host.footer(&root, &foreground)?;
+    let root = root.titled(title, ("sans-serif", 20).into_font().color(&foreground))?;
+    let (upper, lower) = root.split_vertically(root.dim_in_pixel().1 * 3 / 5);
+
+    let mut latency_chart = ChartBuilder::on(&upper)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..max_time * 1.02, 0.0..max_latency * 1.1)?;
+    latency_chart
+        .configure_mesh()
+        .x_desc("Time, s")
+        .y_desc("Response, ms")
+        .axis_style(foreground)
+        .label_style(("sans-serif", 12).into_font().color(&foreground))
+        .axis_desc_style(("sans-serif", 14).into_font().color(&foreground))
+        .bold_line_style(theme.grid())
+        .light_line_style(theme.background())
+        .draw()?;
+
+    let mut rps_chart = ChartBuilder::on(&lower)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..max_time * 1.02, 0.0..max_rps * 1.1)?;
+    rps_chart
+        .configure_mesh()
+        .x_desc("Time, s")
+        .y_desc("Requests/s")
+        .axis_style(foreground)
+        .label_style(("sans-serif", 12).into_font().color(&foreground))
+        .axis_desc_style(("sans-serif", 14).into_font().color(&foreground))
+        .bold_line_style(theme.grid())
+        .light_line_style(theme.background())
+        .draw()?;
+
+    for (idx, label, points) in scenarios {
+        let color = scenario_color(*idx);
+        latency_chart
+            .draw_series(LineSeries::new(
+                points.iter().map(|x| (x.time, x.median)),
+                color.stroke_width(2),
+            ))?
+            .label(format!("{label}, median"))
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 12, y)], color.stroke_width(2)));
+        latency_chart
+            .draw_series(LineSeries::new(
+                points.iter().map(|x| (x.time, x.p99)),
+                color.mix(0.5),
+            ))?
+            .label(format!("{label}, p99"))
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 12, y)], color.mix(0.5)));
+        rps_chart
+            .draw_series(LineSeries::new(
+                points.iter().map(|x| (x.time, x.rps)),
+                color.stroke_width(2),
+            ))?
+            .label(*label)
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 12, y)], color.stroke_width(2)));
+    }
+
+    for chart in [&mut latency_chart, &mut rps_chart] {
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .label_font(("sans-serif", 12).into_font().color(&foreground))
+            .background_style(theme.background().mix(0.8).filled())
+            .border_style(foreground.mix(0.5))
+            .draw()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(offset: f64, latency: f64) -> Sample {
+        Sample {
+            offset,
+            latency,
+            status: 200,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn series_rolls_over_recent_buckets() {
+        // A second long run is split into 10 ms buckets, a point covers the last five of them
+        let samples = [
+            sample(0.001, 1.0),
+            sample(0.002, 3.0),
+            sample(0.012, 5.0),
+            sample(0.505, 10.0),
+            sample(1.0, 2.0),
+        ];
+        let points = series(&samples);
+        assert_eq!(points.len(), 4);
+        assert_close(points[0].time, 0.01);
+        assert_close(points[0].median, 2.0);
+        assert_close(points[0].p99, 2.98);
+        assert_close(points[0].rps, 200.0);
+        assert_close(points[1].time, 0.02);
+        assert_close(points[1].median, 3.0);
+        assert_close(points[1].rps, 150.0);
+        assert_close(points[2].time, 0.51);
+        assert_close(points[2].median, 10.0);
+        assert_close(points[2].rps, 20.0);
+        assert_close(points[3].time, 1.01);
+        assert_close(points[3].median, 2.0);
+    }
+
+    #[test]
+    fn series_of_short_and_empty_runs() {
+        // The bucket is not narrower than the minimum width
+        let points = series(&[sample(0.0, 1.0), sample(0.0, 2.0)]);
+        assert_eq!(points.len(), 1);
+        assert_close(points[0].rps, 2.0 / MIN_BUCKET_WIDTH);
+        assert!(series(&[]).is_empty());
+    }
+}
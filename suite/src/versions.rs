@@ -1,139 +1,85 @@
-use regex::Regex;
-use std::collections::BTreeMap;
-use std::error::Error;
-use std::process::Command;
-
-mod errors;
+#[macro_use]
+extern crate clap;
 
-type StringResult = Result<String, Box<dyn Error>>;
-
-thread_local! {
-    static LDC_PATTERN: Regex = Regex::new(r"LDC - the LLVM D compiler \((.*)\)").unwrap();
-}
+use clap::Arg;
+use std::error::Error;
 
-fn pexec(cmd: &mut Command) -> StringResult {
-    let output = cmd.output()?;
-    let str = String::from_utf8(output.stdout)?;
-    Ok(str)
-}
+use suite::probes;
+use suite::tables::{self, Table};
 
-fn touch(filename: &str) -> StringResult {
-    let mut dir = std::env::temp_dir();
-    dir.push(filename);
-    to_result(dir.to_str().map(String::from))
+fn render(format: &str, langs: &Table, frameworks: &Table) -> Result<String, Box<dyn Error>> {
+    Ok(match format {
+        "json" => serde_json::to_string_pretty(&serde_json::json!({
+            "languages": langs.plain().json(),
+            "frameworks": frameworks.plain().json(),
+        }))?,
+        "markdown" => format!(
+            "{}\n\n{}",
+            langs.plain().markdown(),
+            frameworks.plain().markdown()
+        ),
+        _ => format!("{}\n\n{}", langs.adoc(), frameworks.adoc()),
+    })
 }
 
-fn cat(filename: &str, content: &str) -> StringResult {
-    let path = touch(filename);
-    if let Ok(valid_path) = &path {
-        std::fs::write(valid_path, content)?;
-    }
-    path
-}
-
-fn to_result(opt: Option<String>) -> StringResult {
-    opt.ok_or_else(|| Box::new(errors::ValueIsEmptyError {}) as Box<dyn Error>)
+/// Rewrites the version tables between the `// versions:begin` and `// versions:end` markers.
+fn update_readme(path: &str, content: &str) -> Result<(), Box<dyn Error>> {
+    let readme = std::fs::read_to_string(path)?;
+    let updated = tables::replace_between_markers(&readme, "versions", content)
+        .ok_or_else(|| format!("The version markers are not found in {path}"))?;
+    std::fs::write(path, updated)?;
+    eprintln!("Updated {path}");
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut table = vec!["|===".to_string(), "| Language | Version".to_string()];
+    let matches = clap::Command::new("Simple Web Benchmark versions")
+        .version(crate_version!())
+        .override_usage("cargo run --manifest-path suite/Cargo.toml --bin versions -- [OPTIONS]")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("format")
+                .help("Sets the output format")
+                .value_parser(["adoc", "json", "markdown"])
+                .default_value("adoc"),
+        )
+        .arg(
+            Arg::new("update-readme")
+                .long("update-readme")
+                .value_name("file")
+                .help("Rewrites the version tables in the README between the markers")
+                .num_args(0..=1)
+                .default_missing_value("README.adoc"),
+        )
+        .get_matches();
 
-    let mut langs: BTreeMap<&str, Box<dyn Fn() -> StringResult>> = BTreeMap::new();
-    langs.insert(
-        "C{pp}/g{pp}",
-        Box::new(|| pexec(Command::new("g++").args(["-dumpfullversion"]))),
-    );
-    langs.insert(
-        "Rust",
-        Box::new(|| {
-            let text = pexec(Command::new("rustc").args(["--version"]))?;
-            to_result(text.split_whitespace().nth(1).map(String::from))
-        }),
-    );
-    langs.insert(
-        "D/ldc2",
-        Box::new(|| {
-            let text = pexec(Command::new("ldc2").args(["--version"]))?;
-            LDC_PATTERN.with(|re| {
-                to_result(
-                    re.captures(&text)
-                        .map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
-                        .flatten(),
-                )
-            })
-        }),
-    );
-    langs.insert(
-        "Go",
-        Box::new(|| {
-            let prog = r#"
-package main
-import (
-  "fmt"
-  "runtime"
-)
-func main() {
-  fmt.Printf(runtime.Version())
-}
-"#;
-            pexec(Command::new("go").args(["run", &cat("go.go", prog)?]))
-        }),
-    );
-    langs.insert(
-        "Scala",
-        Box::new(|| pexec(Command::new("scala").args(["version", "--scala"]))),
-    );
-    langs.insert(
-        "Java",
-        Box::new(|| {
-            let prog = r#"
-class Test {
-  public static void main(String[] argv) {
-    System.out.print(System.getProperty("java.version"));
-  }
-}
-"#;
-            pexec(Command::new("java").args([&cat("java.java", prog)?]))
-        }),
-    );
-    langs.insert(
-        "Node.js",
-        Box::new(|| pexec(Command::new("node").args(["-e", "console.log(process.version)"]))),
-    );
-    langs.insert(
-        "Crystal",
-        Box::new(|| pexec(Command::new("crystal").args(["eval", "puts Crystal::VERSION"]))),
-    );
-    langs.insert(
-        "PyPy",
-        Box::new(|| {
-            let prog = r#"
-import platform, sys
-pypy = "%d.%d.%d-%s%d" % sys.pypy_version_info
-print("%s for Python %s" % (pypy, platform.python_version()))
-"#;
-            pexec(Command::new("pypy3").args([&cat("pypy.py", prog)?]))
-        }),
-    );
-    langs.insert(
-        "PHP",
-        Box::new(|| pexec(Command::new("php").args(["-r", "echo phpversion();"]))),
-    );
-
-    for (name, version_lambda) in &langs {
-        eprint!("Fetching {name} version... ");
-        match version_lambda() {
-            Ok(version) => {
-                table.push(format!("\n| {}\n| {}", name, &version));
-                eprintln!("ok");
-            }
-            Err(e) => {
-                eprintln!("fail ({e})");
-            }
+    eprintln!("Fetching versions...");
+    let (langs, frameworks) = probes::run_versions();
+    let outcomes = langs
+        .iter()
+        .map(|(name, outcome)| (name.to_string(), outcome))
+        .chain(
+            frameworks
+                .iter()
+                .map(|((lang, name), outcome)| (format!("{lang}/{name}"), outcome)),
+        );
+    for (name, outcome) in outcomes {
+        match &outcome.detail {
+            Some(detail) => eprintln!("{name}: {} ({detail})", outcome.status),
+            None => eprintln!("{name}: {}", outcome.status),
         }
     }
-    eprintln!("\n");
-    table.push("|===".to_string());
-    println!("{}", table.join("\n"));
+    eprintln!();
+
+    let update_readme_path = matches.get_one::<String>("update-readme");
+    let with_status = update_readme_path.is_none();
+    let (langs_table, frameworks_table) = probes::tables(&langs, &frameworks, with_status);
+    if let Some(path) = update_readme_path {
+        update_readme(path, &render("adoc", &langs_table, &frameworks_table)?)?;
+    } else {
+        let format = matches.get_one::<String>("format").unwrap();
+        println!("{}", render(format, &langs_table, &frameworks_table)?);
+    }
     Ok(())
 }